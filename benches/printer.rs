@@ -0,0 +1,62 @@
+//! Benchmarks of the parser and the printer with the test diffs. The printer
+//! writes to a sink, so only the rendering is measured and not the terminal.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use diff_rs::{PrintOptions, Printer, StreamParser};
+use std::io;
+
+// the test diffs repeated to a larger input
+const REPEAT: usize = 20;
+
+/// Returns the input with the test diffs of git, `git log -p` and `diff -u`
+fn get_input() -> String {
+    [
+        include_str!("../test/resources/diff.patch"),
+        include_str!("../test/resources/log.patch"),
+        include_str!("../test/resources/unified.patch"),
+        include_str!("../test/resources/combined.patch"),
+    ]
+    .concat()
+    .repeat(REPEAT)
+}
+
+fn parse_benchmark(c: &mut Criterion) {
+    let input = get_input();
+    c.bench_function("parse_sections", |b| {
+        b.iter(|| diff_rs::parse_sections(black_box(&input)))
+    });
+    c.bench_function("stream_parser", |b| {
+        b.iter(|| {
+            let mut stream_parser = StreamParser::new();
+            let mut count = 0;
+            for line in black_box(&input).split_inclusive('\n') {
+                count += stream_parser.push_line(line).len();
+            }
+            count + stream_parser.finish().len()
+        })
+    });
+}
+
+fn print_benchmark(c: &mut Criterion) {
+    let sections = diff_rs::parse_sections(&get_input());
+    for (name, columnview, syntax) in [
+        ("print", false, false),
+        ("print_column", true, false),
+        ("print_syntax", false, true),
+    ] {
+        let mut options = PrintOptions::default();
+        options.columnview = columnview;
+        options.syntax = syntax;
+        let printer = Printer::new(&options);
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                sections
+                    .iter()
+                    .try_for_each(|section| printer.print_section(&mut io::sink(), section))
+            })
+        });
+    }
+}
+
+criterion_group!(benches, parse_benchmark, print_benchmark);
+criterion_main!(benches);
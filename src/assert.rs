@@ -0,0 +1,219 @@
+//! Assertions for tests which show the difference of the values as a diff,
+//! like `assert_eq!` but readable for long texts and large structs.
+//!
+//! `assert_diff!` compares two texts, `assert_debug_diff!` two values by
+//! their pretty printed `Debug` output. On a failure the diff of the expected
+//! to the actual lines is printed by the printer of diff-rs, with colours on
+//! a terminal and as plain text otherwise, e.g. in the log of a CI.
+
+use std::fmt;
+use std::io::{self, IsTerminal};
+
+use crate::diff::{diff_texts, DiffOptions};
+use crate::file::{File, MODIFIER, SECTION};
+use crate::printer::{print, PrintOptions};
+
+// the names of both sides in the header of the diff
+const EXPECTED_NAME: &str = "expected";
+const ACTUAL_NAME: &str = "actual";
+
+/// Asserts that two texts are equal, otherwise panics with the diff of the
+/// expected to the actual lines. Both texts can be anything which derefs to
+/// `str`, e.g. `String` or `&str`. An additional message can be given like
+/// for `assert_eq!`.
+///
+/// ```
+/// use diff_rs::assert_diff;
+///
+/// let text = String::from("apples\npears\n");
+/// assert_diff!("apples\npears\n", text);
+/// assert_diff!("apples\npears\n", text, "the list of {}", "fruits");
+/// ```
+#[macro_export]
+macro_rules! assert_diff {
+    ($expected:expr, $actual:expr $(,)?) => {{
+        let expected: &str = &$expected;
+        let actual: &str = &$actual;
+        if expected != actual {
+            $crate::assert_diff_failed(expected, actual, ::std::option::Option::None);
+        }
+    }};
+    ($expected:expr, $actual:expr, $($arg:tt)+) => {{
+        let expected: &str = &$expected;
+        let actual: &str = &$actual;
+        if expected != actual {
+            $crate::assert_diff_failed(
+                expected,
+                actual,
+                ::std::option::Option::Some(::std::format_args!($($arg)+)),
+            );
+        }
+    }};
+}
+
+/// Asserts that two values are equal (`PartialEq`), otherwise panics with
+/// the diff of their pretty printed `Debug` output (`{:#?}`). An additional
+/// message can be given like for `assert_eq!`.
+///
+/// ```
+/// use diff_rs::assert_debug_diff;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Fruit {
+///     name: &'static str,
+///     count: usize,
+/// }
+///
+/// let fruit = Fruit { name: "apple", count: 3 };
+/// assert_debug_diff!(Fruit { name: "apple", count: 3 }, fruit);
+/// ```
+#[macro_export]
+macro_rules! assert_debug_diff {
+    ($expected:expr, $actual:expr $(,)?) => {
+        match (&$expected, &$actual) {
+            (expected, actual) => {
+                if !(*expected == *actual) {
+                    $crate::assert_diff_failed(
+                        &::std::format!("{:#?}", expected),
+                        &::std::format!("{:#?}", actual),
+                        ::std::option::Option::None,
+                    );
+                }
+            }
+        }
+    };
+    ($expected:expr, $actual:expr, $($arg:tt)+) => {
+        match (&$expected, &$actual) {
+            (expected, actual) => {
+                if !(*expected == *actual) {
+                    $crate::assert_diff_failed(
+                        &::std::format!("{:#?}", expected),
+                        &::std::format!("{:#?}", actual),
+                        ::std::option::Option::Some(::std::format_args!($($arg)+)),
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Panics with the diff of the expected to the actual text, the failure of
+/// the assertion macros. Not part of the public API.
+///
+/// # Arguments
+///
+/// * `expected` - the expected text
+/// * `actual` - the actual text
+/// * `message` - the additional message of the assertion
+///
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_diff_failed(expected: &str, actual: &str, message: Option<fmt::Arguments<'_>>) -> ! {
+    let diff_output = get_diff_output(expected, actual, io::stderr().is_terminal());
+    match message {
+        Some(message) => panic!(
+            "assertion failed: `(expected == actual)`: {}\n\n{}",
+            message, diff_output
+        ),
+        None => panic!(
+            "assertion failed: `(expected == actual)`\n\n{}",
+            diff_output
+        ),
+    }
+}
+
+/// Returns the printed diff of the expected to the actual text
+///
+/// # Arguments
+///
+/// * `expected` - the expected text
+/// * `actual` - the actual text
+/// * `colour` - paint the diff, only on a terminal
+///
+fn get_diff_output(expected: &str, actual: &str, colour: bool) -> String {
+    let mut file = File::new(
+        MODIFIER::MODIFIED,
+        ACTUAL_NAME.to_string(),
+        String::new(),
+        diff_texts(expected, actual, &DiffOptions::default()),
+    );
+    file.old_filename = Some(EXPECTED_NAME.to_string());
+
+    let options = PrintOptions {
+        syntax: false,
+        colour,
+        ..PrintOptions::default()
+    };
+    let mut output = Vec::new();
+    // writing to a vector can't fail
+    let _ = print(&mut output, &[SECTION::FILE(file)], &options);
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+/* --------------------------------------------------------- */
+/* ------------------------- TESTS ------------------------- */
+/* --------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Fruit {
+        name: &'static str,
+        count: usize,
+    }
+
+    #[test]
+    fn assert_diff_test() {
+        assert_diff!("apples\npears\n", String::from("apples\npears\n"));
+        assert_debug_diff!(
+            Fruit {
+                name: "apple",
+                count: 3,
+            },
+            Fruit {
+                name: "apple",
+                count: 3,
+            },
+            "the same fruit"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: `(expected == actual)`: the list of fruits")]
+    fn assert_diff_failed_test() {
+        assert_diff!(
+            "apples\npears\n",
+            "apples\nplums\n",
+            "the list of {}",
+            "fruits"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "count: 4")]
+    fn assert_debug_diff_failed_test() {
+        assert_debug_diff!(
+            Fruit {
+                name: "apple",
+                count: 3,
+            },
+            Fruit {
+                name: "apple",
+                count: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn get_diff_output_test() {
+        let output = get_diff_output("apples\npears\n", "apples\nplums\n", false);
+        assert!(!output.contains('\u{1b}'));
+        assert!(output.contains("expected → actual"));
+        assert!(output.contains("│-pears\n"));
+        assert!(output.contains("│+plums\n"));
+
+        let output = get_diff_output("apples\npears\n", "apples\nplums\n", true);
+        assert!(output.contains("\u{1b}[31m-pears\u{1b}[0m"));
+    }
+}
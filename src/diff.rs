@@ -0,0 +1,1020 @@
+//! Computes the diff of two texts line by line, so no `diff` program is
+//! needed, e.g. to compare two files or for the assertions of tests. The
+//! shortest edit script is found with the algorithm of Myers, the patience
+//! and the histogram algorithm of git are available as well. The changes are
+//! grouped to hunks with unchanged context lines around them.
+//!
+//! The result uses the same structs (`File`, `Hunk`, `LINE`) as a parsed
+//! diff, so it is printed the same way.
+
+#![allow(clippy::upper_case_acronyms)]
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::file::{Binary, File, Hunk, BINARY_SIZE, CHANGE, LINE, MODIFIER};
+
+/// The count of unchanged lines before and after the changes of a hunk
+pub const DEFAULT_CONTEXT: usize = 3;
+// the most occurrences of a line in the old lines to split the histogram
+// diff at it, like in git
+const HISTOGRAM_MAX_OCCURRENCES: usize = 64;
+// the count of bytes at the start of a file which are checked for a null
+// byte of a binary file, like in git
+const BINARY_CHECK_SIZE: usize = 8000;
+
+/// The algorithm to find the changed lines
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum ALGORITHM {
+    /// the shortest edit script by Myers, like `diff` and git by default
+    MYERS,
+    /// aligns the lines which are unique in both texts first, e.g. the
+    /// function signatures, and diffs the lines between them
+    PATIENCE,
+    /// splits the texts at their rarest common lines, like the patience
+    /// algorithm but also for repeated lines
+    HISTOGRAM,
+}
+
+/// The settings of the diff. New options can be added in a minor version, so
+/// start with the defaults and change the fields.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct DiffOptions {
+    pub algorithm: ALGORITHM,
+    /// the count of unchanged lines around the changes
+    pub context: usize,
+}
+
+impl Default for DiffOptions {
+    fn default() -> DiffOptions {
+        DiffOptions {
+            algorithm: ALGORITHM::MYERS,
+            context: DEFAULT_CONTEXT,
+        }
+    }
+}
+
+/// A line of a text, the content is compared with its newline, so a missing
+/// newline at the end is a change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Line<'a> {
+    content: &'a str,
+    no_newline: bool,
+}
+
+/// Returns the hunks of the changes between two texts
+///
+/// # Arguments
+///
+/// * `old` - the old text
+/// * `new` - the new text
+/// * `options` - the algorithm and the count of context lines
+///
+pub fn diff_texts(old: &str, new: &str, options: &DiffOptions) -> Vec<Hunk> {
+    let old_lines = split_lines(old);
+    let new_lines = split_lines(new);
+    let edits = get_edits(&old_lines, &new_lines, options.algorithm);
+    get_hunks(&old_lines, &new_lines, &edits, options.context)
+}
+
+/// Returns the changed files of two files or of two directories with all
+/// files in them, like `diff -rN`. A file is compared with the file of the
+/// same name in a directory. Equal files are left out.
+///
+/// # Arguments
+///
+/// * `old` - the path of the old file or directory
+/// * `new` - the path of the new file or directory
+/// * `options` - the algorithm and the count of context lines
+///
+pub fn diff_paths(old: &Path, new: &Path, options: &DiffOptions) -> io::Result<Vec<File>> {
+    let old_dir = fs::metadata(old)
+        .map_err(|e| get_path_error(old, e))?
+        .is_dir();
+    let new_dir = fs::metadata(new)
+        .map_err(|e| get_path_error(new, e))?
+        .is_dir();
+    let mut files: Vec<File> = Vec::new();
+
+    match (old_dir, new_dir) {
+        (true, true) => push_dir_files(&mut files, Some(old), Some(new), "", options)?,
+        (false, false) => {
+            let old_name = old.to_string_lossy();
+            let new_name = new.to_string_lossy();
+            // the paths are followed and may be pipes, e.g. of `<(command)`
+            let read = |path: &Path| fs::read(path).map_err(|e| get_path_error(path, e));
+            let (old_content, new_content) = (read(old)?, read(new)?);
+            if let Some(mut file) =
+                get_file(Some(&old_content), Some(&new_content), &new_name, options)
+            {
+                if old_name != new_name {
+                    file.old_filename = Some(old_name.into_owned());
+                }
+                files.push(file);
+            }
+        }
+        (true, false) => {
+            let old = old.join(new.file_name().unwrap_or_default());
+            files.extend(diff_paths(&old, new, options)?);
+        }
+        (false, true) => {
+            let new = new.join(old.file_name().unwrap_or_default());
+            files.extend(diff_paths(old, &new, options)?);
+        }
+    }
+    Ok(files)
+}
+
+/// The type of an entry of a compared directory, symlinks aren't followed
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EntryKind {
+    Missing,
+    Dir,
+    File,
+    Symlink,
+}
+
+/// Returns the type of an entry of a directory. Other entries than files,
+/// directories and symlinks, e.g. pipes, are an error, like in git.
+///
+/// # Arguments
+///
+/// * `path` - the path of the entry or `None` if its directory is missing
+///
+fn get_entry_kind(path: Option<&Path>) -> io::Result<EntryKind> {
+    let path = match path {
+        Some(path) => path,
+        None => return Ok(EntryKind::Missing),
+    };
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => Ok(EntryKind::Dir),
+        Ok(metadata) if metadata.is_file() => Ok(EntryKind::File),
+        Ok(metadata) if metadata.file_type().is_symlink() => Ok(EntryKind::Symlink),
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{}: unsupported file type", path.display()),
+        )),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(EntryKind::Missing),
+        Err(e) => Err(get_path_error(path, e)),
+    }
+}
+
+/// Returns the content of an entry of a directory, the content of a symlink
+/// is its target like in git, or `None` if it is missing
+///
+/// # Arguments
+///
+/// * `path` - the path of the entry
+/// * `kind` - the type of the entry
+///
+fn read_entry(path: Option<&Path>, kind: EntryKind) -> io::Result<Option<Vec<u8>>> {
+    match (path, kind) {
+        (Some(path), EntryKind::File) => fs::read(path)
+            .map(Some)
+            .map_err(|e| get_path_error(path, e)),
+        (Some(path), EntryKind::Symlink) => fs::read_link(path)
+            .map(|target| Some(target.to_string_lossy().into_owned().into_bytes()))
+            .map_err(|e| get_path_error(path, e)),
+        _ => Ok(None),
+    }
+}
+
+/// Adds the changed files of two directories and their subdirectories, the
+/// files of only one directory are added or deleted. A file which is
+/// replaced by a directory or a symlink is deleted and the new one added.
+///
+/// # Arguments
+///
+/// * `files` - the changed files
+/// * `old` - the old directory or `None` if it is missing
+/// * `new` - the new directory or `None` if it is missing
+/// * `prefix` - the path of the directories in the compared directories
+/// * `options` - the algorithm and the count of context lines
+///
+fn push_dir_files(
+    files: &mut Vec<File>,
+    old: Option<&Path>,
+    new: Option<&Path>,
+    prefix: &str,
+    options: &DiffOptions,
+) -> io::Result<()> {
+    let mut names: Vec<String> = Vec::new();
+    for dir in [old, new].iter().flatten() {
+        for entry in fs::read_dir(dir).map_err(|e| get_path_error(dir, e))? {
+            let entry = entry.map_err(|e| get_path_error(dir, e))?;
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    names.sort();
+    names.dedup();
+
+    for name in names {
+        let old_path = old.map(|dir| dir.join(&name));
+        let new_path = new.map(|dir| dir.join(&name));
+        let old_kind = get_entry_kind(old_path.as_deref())?;
+        let new_kind = get_entry_kind(new_path.as_deref())?;
+        let filename = format!("{}{}", prefix, name);
+
+        let (old_dir, new_dir) = (old_kind == EntryKind::Dir, new_kind == EntryKind::Dir);
+        let (old_entry, new_entry) = (
+            read_entry(old_path.as_deref(), old_kind)?,
+            read_entry(new_path.as_deref(), new_kind)?,
+        );
+        // a file replaced by a symlink is deleted and added, the directories
+        // have no content and are compared below
+        if old_kind == new_kind || old_entry.is_none() || new_entry.is_none() {
+            files.extend(get_file(
+                old_entry.as_deref(),
+                new_entry.as_deref(),
+                &filename,
+                options,
+            ));
+        } else {
+            files.extend(get_file(old_entry.as_deref(), None, &filename, options));
+            files.extend(get_file(None, new_entry.as_deref(), &filename, options));
+        }
+        if old_dir || new_dir {
+            push_dir_files(
+                files,
+                old_path.as_deref().filter(|_| old_dir),
+                new_path.as_deref().filter(|_| new_dir),
+                &format!("{}/", filename),
+                options,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the changes of two files or `None` if they are equal or both are
+/// missing. A missing file is added or deleted, a binary file has only the
+/// sizes.
+///
+/// # Arguments
+///
+/// * `old` - the content of the old file or `None` if it is missing
+/// * `new` - the content of the new file or `None` if it is missing
+/// * `filename` - the filename of the changed file
+/// * `options` - the algorithm and the count of context lines
+///
+fn get_file(
+    old: Option<&[u8]>,
+    new: Option<&[u8]>,
+    filename: &str,
+    options: &DiffOptions,
+) -> Option<File> {
+    let modifier = match (old, new) {
+        (None, None) => return None,
+        (Some(old), Some(new)) if old == new => return None,
+        (None, _) => MODIFIER::ADD,
+        (_, None) => MODIFIER::DELETE,
+        _ => MODIFIER::MODIFIED,
+    };
+    let (old_content, new_content) = (old.unwrap_or_default(), new.unwrap_or_default());
+
+    let mut file = File::new(modifier, filename.to_string(), String::new(), Vec::new());
+    match (get_text(old_content), get_text(new_content)) {
+        (Some(old_text), Some(new_text)) => {
+            file.hunks = diff_texts(old_text, new_text, options);
+        }
+        _ => {
+            file.binary = Some(Binary {
+                old_size: old.map(|content| BINARY_SIZE::LITERAL(content.len())),
+                new_size: new.map(|content| BINARY_SIZE::LITERAL(content.len())),
+            })
+        }
+    }
+    Some(file)
+}
+
+/// Returns the content of a file as text or `None` for a binary file, which
+/// has a null byte at its start or is no UTF-8
+fn get_text(content: &[u8]) -> Option<&str> {
+    if content
+        .iter()
+        .take(BINARY_CHECK_SIZE)
+        .any(|byte| *byte == 0)
+    {
+        return None;
+    }
+    std::str::from_utf8(content).ok()
+}
+
+/// Returns the error with the path in front of its message
+fn get_path_error(path: &Path, error: io::Error) -> io::Error {
+    io::Error::new(error.kind(), format!("{}: {}", path.display(), error))
+}
+
+/// Splits a text into its lines, only the last line can be without newline
+fn split_lines(text: &str) -> Vec<Line<'_>> {
+    text.split_inclusive('\n')
+        .map(|line| match line.strip_suffix('\n') {
+            Some(content) => Line {
+                content,
+                no_newline: false,
+            },
+            None => Line {
+                content: line,
+                no_newline: true,
+            },
+        })
+        .collect()
+}
+
+/// Returns the edit script from the old to the new lines. A removed line is a
+/// step in the old lines, an added line in the new lines and an unchanged
+/// line in both. The equal lines at the start and the end are skipped before
+/// the algorithm is used.
+///
+/// # Arguments
+///
+/// * `old` - the lines of the old text
+/// * `new` - the lines of the new text
+/// * `algorithm` - the algorithm for the lines between the equal lines
+///
+fn get_edits(old: &[Line<'_>], new: &[Line<'_>], algorithm: ALGORITHM) -> Vec<CHANGE> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_rest = &old[prefix..old.len() - suffix];
+    let new_rest = &new[prefix..new.len() - suffix];
+
+    let mut edits = vec![CHANGE::NOP; prefix];
+    if old_rest.is_empty() || new_rest.is_empty() {
+        edits.extend(vec![CHANGE::REM; old_rest.len()]);
+        edits.extend(vec![CHANGE::ADD; new_rest.len()]);
+    } else {
+        match algorithm {
+            ALGORITHM::MYERS => push_myers_edits(&mut edits, old_rest, new_rest),
+            ALGORITHM::PATIENCE => push_patience_edits(&mut edits, old_rest, new_rest),
+            ALGORITHM::HISTOGRAM => push_histogram_edits(&mut edits, old_rest, new_rest),
+        }
+    }
+    edits.extend(vec![CHANGE::NOP; suffix]);
+    edits
+}
+
+/// Adds the shortest edit script by the algorithm of Myers in linear space.
+/// The middle of the shortest path is searched from both ends at once, the
+/// parts before and after it are diffed on their own.
+///
+/// # Arguments
+///
+/// * `edits` - the edit script
+/// * `old` - the lines of the old text, the first and the last line differ
+///   from the new lines
+/// * `new` - the lines of the new text
+///
+fn push_myers_edits(edits: &mut Vec<CHANGE>, old: &[Line<'_>], new: &[Line<'_>]) {
+    match get_middle_point(old, new) {
+        Some((x, y)) => {
+            edits.extend(get_edits(&old[..x], &new[..y], ALGORITHM::MYERS));
+            edits.extend(get_edits(&old[x..], &new[y..], ALGORITHM::MYERS));
+        }
+        None => {
+            edits.extend(vec![CHANGE::REM; old.len()]);
+            edits.extend(vec![CHANGE::ADD; new.len()]);
+        }
+    }
+}
+
+/// Returns a point on the shortest path through the edit graph where the
+/// searches from the start and from the end meet, `None` if the texts have
+/// no common line. Every search keeps the furthest reached position on each
+/// diagonal `k` for the count of changes `d`.
+fn get_middle_point(old: &[Line<'_>], new: &[Line<'_>]) -> Option<(usize, usize)> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max_d = (n + m + 1) / 2;
+    let offset = max_d;
+    let length = 2 * max_d as usize + 2;
+    let mut forward = vec![-1isize; length];
+    let mut backward = vec![-1isize; length];
+    forward[offset as usize + 1] = 0;
+    backward[offset as usize + 1] = 0;
+    let delta = n - m;
+    // the searches meet in the forward search for an odd delta
+    let front = delta % 2 != 0;
+    // the diagonals which left the edit graph
+    let (mut forward_start, mut forward_end) = (0, 0);
+    let (mut backward_start, mut backward_end) = (0, 0);
+    let get_index = |k: isize| (offset + k) as usize;
+    let is_index = |index: isize| index >= 0 && index < length as isize;
+
+    for d in 0..max_d {
+        for k in (-d + forward_start..=d - forward_end).step_by(2) {
+            let mut x =
+                if k == -d || (k != d && forward[get_index(k - 1)] < forward[get_index(k + 1)]) {
+                    forward[get_index(k + 1)]
+                } else {
+                    forward[get_index(k - 1)] + 1
+                };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[get_index(k)] = x;
+            if x > n {
+                forward_end += 2;
+            } else if y > m {
+                forward_start += 2;
+            } else if front {
+                let backward_index = offset + delta - k;
+                if is_index(backward_index)
+                    && backward[backward_index as usize] != -1
+                    && x >= n - backward[backward_index as usize]
+                {
+                    return Some((x as usize, y as usize));
+                }
+            }
+        }
+
+        for k in (-d + backward_start..=d - backward_end).step_by(2) {
+            let mut x =
+                if k == -d || (k != d && backward[get_index(k - 1)] < backward[get_index(k + 1)]) {
+                    backward[get_index(k + 1)]
+                } else {
+                    backward[get_index(k - 1)] + 1
+                };
+            let mut y = x - k;
+            while x < n && y < m && old[(n - x - 1) as usize] == new[(m - y - 1) as usize] {
+                x += 1;
+                y += 1;
+            }
+            backward[get_index(k)] = x;
+            if x > n {
+                backward_end += 2;
+            } else if y > m {
+                backward_start += 2;
+            } else if !front {
+                let forward_index = offset + delta - k;
+                if is_index(forward_index) && forward[forward_index as usize] != -1 {
+                    let forward_x = forward[forward_index as usize];
+                    let forward_y = forward_x - (forward_index - offset);
+                    if forward_x >= n - x {
+                        return Some((forward_x as usize, forward_y as usize));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Adds the edit script of the patience algorithm. The lines which are
+/// unique in both texts are aligned by their longest common subsequence, the
+/// lines between them are diffed again. Without unique lines the edit script
+/// of Myers is used.
+///
+/// # Arguments
+///
+/// * `edits` - the edit script
+/// * `old` - the lines of the old text
+/// * `new` - the lines of the new text
+///
+fn push_patience_edits(edits: &mut Vec<CHANGE>, old: &[Line<'_>], new: &[Line<'_>]) {
+    let anchors = get_patience_anchors(old, new);
+    if anchors.is_empty() {
+        return push_myers_edits(edits, old, new);
+    }
+
+    let (mut old_start, mut new_start) = (0, 0);
+    for (old_pos, new_pos) in anchors {
+        edits.extend(get_edits(
+            &old[old_start..old_pos],
+            &new[new_start..new_pos],
+            ALGORITHM::PATIENCE,
+        ));
+        edits.push(CHANGE::NOP);
+        old_start = old_pos + 1;
+        new_start = new_pos + 1;
+    }
+    edits.extend(get_edits(
+        &old[old_start..],
+        &new[new_start..],
+        ALGORITHM::PATIENCE,
+    ));
+}
+
+/// Returns the positions in the old and the new lines of the longest
+/// sequence of lines which are unique in both texts and in the same order,
+/// found by patience sorting
+fn get_patience_anchors(old: &[Line<'_>], new: &[Line<'_>]) -> Vec<(usize, usize)> {
+    // the counts in the old and the new lines and the position in the old
+    let mut counts: HashMap<&Line<'_>, (usize, usize, usize)> = HashMap::new();
+    for (i, line) in old.iter().enumerate() {
+        counts.entry(line).or_insert((0, 0, i)).0 += 1;
+    }
+    for line in new {
+        if let Some(count) = counts.get_mut(line) {
+            count.1 += 1;
+        }
+    }
+    let unique: Vec<(usize, usize)> = new
+        .iter()
+        .enumerate()
+        .filter_map(|(j, line)| match counts.get(line) {
+            Some((1, 1, i)) => Some((*i, j)),
+            _ => None,
+        })
+        .collect();
+
+    // every pile keeps its top line, every line the top of the pile before
+    let mut piles: Vec<usize> = Vec::new();
+    let mut previous: Vec<Option<usize>> = Vec::with_capacity(unique.len());
+    for (n, (i, _)) in unique.iter().enumerate() {
+        let pile = piles.partition_point(|top| unique[*top].0 < *i);
+        previous.push(pile.checked_sub(1).map(|pile| piles[pile]));
+        if pile == piles.len() {
+            piles.push(n);
+        } else {
+            piles[pile] = n;
+        }
+    }
+
+    let mut anchors: Vec<(usize, usize)> = Vec::new();
+    let mut top = piles.last().copied();
+    while let Some(n) = top {
+        anchors.push(unique[n]);
+        top = previous[n];
+    }
+    anchors.reverse();
+    anchors
+}
+
+/// Adds the edit script of the histogram algorithm. The texts are split at
+/// the longest common block with the rarest line, the parts before and after
+/// it are diffed again. Without common lines the edit script of Myers is
+/// used.
+///
+/// # Arguments
+///
+/// * `edits` - the edit script
+/// * `old` - the lines of the old text
+/// * `new` - the lines of the new text
+///
+fn push_histogram_edits(edits: &mut Vec<CHANGE>, old: &[Line<'_>], new: &[Line<'_>]) {
+    match get_histogram_block(old, new) {
+        Some((old_pos, new_pos, length)) => {
+            edits.extend(get_edits(
+                &old[..old_pos],
+                &new[..new_pos],
+                ALGORITHM::HISTOGRAM,
+            ));
+            edits.extend(vec![CHANGE::NOP; length]);
+            edits.extend(get_edits(
+                &old[old_pos + length..],
+                &new[new_pos + length..],
+                ALGORITHM::HISTOGRAM,
+            ));
+        }
+        None => push_myers_edits(edits, old, new),
+    }
+}
+
+/// Returns the positions in the old and the new lines and the length of the
+/// common block whose rarest line has the fewest occurrences in the old
+/// lines, the longest of them on a tie. Of equal blocks the one nearest to
+/// the middle is taken, so the parts to diff again are about the same size.
+fn get_histogram_block(old: &[Line<'_>], new: &[Line<'_>]) -> Option<(usize, usize, usize)> {
+    let mut positions: HashMap<&Line<'_>, Vec<usize>> = HashMap::new();
+    for (i, line) in old.iter().enumerate() {
+        positions.entry(line).or_default().push(i);
+    }
+
+    // the occurrences of the rarest line, the length, the distance to the
+    // middle and the positions
+    let mut block: Option<(usize, usize, usize, usize, usize)> = None;
+    for (j, line) in new.iter().enumerate() {
+        let starts = match positions.get(line) {
+            Some(starts) if starts.len() <= HISTOGRAM_MAX_OCCURRENCES => starts,
+            _ => continue,
+        };
+        for i in starts {
+            // only the start of a common block
+            if *i > 0 && j > 0 && old[i - 1] == new[j - 1] {
+                continue;
+            }
+            let length = old[*i..]
+                .iter()
+                .zip(&new[j..])
+                .take_while(|(a, b)| a == b)
+                .count();
+            let occurrences = old[*i..i + length]
+                .iter()
+                .map(|line| positions[line].len())
+                .min()
+                .unwrap_or(0);
+            // twice the distance of the middle of the block to the middle
+            let distance = (2 * j + length).abs_diff(new.len());
+            // `Option::is_none_or` needs Rust 1.82
+            let is_better = match block {
+                Some((best, best_length, best_distance, _, _)) => {
+                    (occurrences, best_length, distance) < (best, length, best_distance)
+                }
+                None => true,
+            };
+            if is_better {
+                block = Some((occurrences, length, distance, *i, j));
+            }
+        }
+    }
+    block.map(|(_, length, _, i, j)| (i, j, length))
+}
+
+/// Groups the edits to hunks, changes with at most twice the context of
+/// unchanged lines between them are in the same hunk
+///
+/// # Arguments
+///
+/// * `old` - the lines of the old text
+/// * `new` - the lines of the new text
+/// * `edits` - the edit script from the old to the new lines
+/// * `context` - the count of unchanged lines around the changes
+///
+fn get_hunks(old: &[Line<'_>], new: &[Line<'_>], edits: &[CHANGE], context: usize) -> Vec<Hunk> {
+    // the count of old and new lines before every edit
+    let mut positions: Vec<(usize, usize)> = Vec::with_capacity(edits.len() + 1);
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for edit in edits {
+        positions.push((old_pos, new_pos));
+        match edit {
+            CHANGE::ADD => new_pos += 1,
+            CHANGE::REM => old_pos += 1,
+            CHANGE::NOP => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+        }
+    }
+    positions.push((old_pos, new_pos));
+
+    let changes: Vec<usize> = (0..edits.len())
+        .filter(|i| edits[*i] != CHANGE::NOP)
+        .collect();
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for i in changes {
+        match ranges.last_mut() {
+            Some((_, last)) if i - *last - 1 <= context.saturating_mul(2) => *last = i,
+            _ => ranges.push((i, i)),
+        }
+    }
+
+    ranges
+        .iter()
+        .map(|(first, last)| {
+            let start = first.saturating_sub(context);
+            let end = last
+                .saturating_add(context)
+                .saturating_add(1)
+                .min(edits.len());
+            let (old_start, new_start) = positions[start];
+            let (old_end, new_end) = positions[end];
+
+            let mut content: Vec<LINE> = Vec::new();
+            for (edit, (old_pos, new_pos)) in edits[start..end].iter().zip(&positions[start..end]) {
+                content.push(match edit {
+                    CHANGE::ADD => LINE::ADD {
+                        number: new_pos + 1,
+                        line: new[*new_pos].content.to_string(),
+                        no_newline: new[*new_pos].no_newline,
+                    },
+                    CHANGE::REM => LINE::REM {
+                        number: old_pos + 1,
+                        line: old[*old_pos].content.to_string(),
+                        no_newline: old[*old_pos].no_newline,
+                    },
+                    CHANGE::NOP => LINE::NOP {
+                        number_left: old_pos + 1,
+                        number_right: new_pos + 1,
+                        line: new[*new_pos].content.to_string(),
+                        no_newline: new[*new_pos].no_newline,
+                    },
+                });
+            }
+
+            // an empty side starts at the line before it
+            let old_count = old_end - old_start;
+            let new_count = new_end - new_start;
+            Hunk::new(
+                (
+                    old_start + usize::from(old_count > 0),
+                    old_count,
+                    new_start + usize::from(new_count > 0),
+                    new_count,
+                ),
+                String::new(),
+                content,
+            )
+        })
+        .collect()
+}
+
+/* --------------------------------------------------------- */
+/* ------------------------- TESTS ------------------------- */
+/* --------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_text_edits(old: &str, new: &str) -> Vec<CHANGE> {
+        get_edits(&split_lines(old), &split_lines(new), ALGORITHM::MYERS)
+    }
+
+    fn get_options(algorithm: ALGORITHM, context: usize) -> DiffOptions {
+        DiffOptions { algorithm, context }
+    }
+
+    /// Returns the new text from the old text and the edit script
+    fn apply_edits(old: &str, new: &str, edits: &[CHANGE]) -> String {
+        let (mut old_lines, mut new_lines) = (old.split_inclusive('\n'), new.split_inclusive('\n'));
+        let mut text = String::new();
+        for edit in edits {
+            match edit {
+                CHANGE::NOP => {
+                    let line = old_lines.next().unwrap();
+                    assert_eq!(Some(line), new_lines.next());
+                    text.push_str(line);
+                }
+                CHANGE::REM => {
+                    old_lines.next().unwrap();
+                }
+                CHANGE::ADD => text.push_str(new_lines.next().unwrap()),
+            }
+        }
+        assert_eq!(None, old_lines.next());
+        text
+    }
+
+    #[test]
+    fn get_edits_test() {
+        assert_eq!(
+            vec![
+                CHANGE::REM,
+                CHANGE::ADD,
+                CHANGE::NOP,
+                CHANGE::REM,
+                CHANGE::NOP,
+                CHANGE::NOP,
+                CHANGE::REM,
+                CHANGE::NOP,
+                CHANGE::ADD,
+            ],
+            get_text_edits("a\nb\nc\na\nb\nb\na\n", "c\nb\na\nb\na\nc\n")
+        );
+        assert_eq!(vec![CHANGE::ADD, CHANGE::ADD], get_text_edits("", "a\nb\n"));
+        assert_eq!(vec![CHANGE::REM], get_text_edits("a\n", ""));
+        assert_eq!(Vec::<CHANGE>::new(), get_text_edits("", ""));
+        // the missing newline at the end is a change
+        assert_eq!(
+            vec![CHANGE::NOP, CHANGE::REM, CHANGE::ADD],
+            get_text_edits("a\nb\n", "a\nb")
+        );
+    }
+
+    #[test]
+    fn diff_test() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+        let new = "1\n2\nthree\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13";
+        let hunks = diff_texts(old, new, &get_options(ALGORITHM::MYERS, 1));
+
+        assert_eq!(2, hunks.len());
+        assert_eq!(
+            Hunk::new(
+                (2, 3, 2, 3),
+                String::new(),
+                vec![
+                    LINE::NOP {
+                        number_left: 2,
+                        number_right: 2,
+                        line: "2".into(),
+                        no_newline: false,
+                    },
+                    LINE::REM {
+                        number: 3,
+                        line: "3".into(),
+                        no_newline: false,
+                    },
+                    LINE::ADD {
+                        number: 3,
+                        line: "three".into(),
+                        no_newline: false,
+                    },
+                    LINE::NOP {
+                        number_left: 4,
+                        number_right: 4,
+                        line: "4".into(),
+                        no_newline: false,
+                    },
+                ]
+            ),
+            hunks[0]
+        );
+        assert_eq!((12, 1, 12, 2), hunks[1].line_info);
+        assert_eq!(
+            LINE::ADD {
+                number: 13,
+                line: "13".into(),
+                no_newline: true,
+            },
+            hunks[1].content[1]
+        );
+
+        // the changes are closer than twice the context
+        assert_eq!(
+            1,
+            diff_texts(old, new, &get_options(ALGORITHM::MYERS, 5)).len()
+        );
+        assert_eq!(
+            (0, 0, 1, 1),
+            diff_texts("", "a\n", &DiffOptions::default())[0].line_info
+        );
+        assert!(diff_texts(old, old, &DiffOptions::default()).is_empty());
+
+        // all lines are context, without an overflow
+        let hunks = diff_texts(old, new, &get_options(ALGORITHM::MYERS, usize::MAX));
+        assert_eq!(1, hunks.len());
+        assert_eq!((1, 12, 1, 13), hunks[0].line_info);
+    }
+
+    #[test]
+    fn get_edits_algorithms_test() {
+        let texts = [
+            ("a\nb\nc\na\nb\nb\na\n", "c\nb\na\nb\na\nc\n"),
+            ("a\nx\nb\nx\nc\n", "b\nx\na\nx\nc\n"),
+            ("x\ny\nx\nz\n", "z\nx\ny\n"),
+            ("1\n2\n3\n", "4\n5\n"),
+            ("a\nb", "a\nb\nc\n"),
+        ];
+        for algorithm in [ALGORITHM::MYERS, ALGORITHM::PATIENCE, ALGORITHM::HISTOGRAM] {
+            for (old, new) in texts {
+                let edits = get_edits(&split_lines(old), &split_lines(new), algorithm);
+                assert_eq!(new, apply_edits(old, new, &edits), "{:?}", algorithm);
+            }
+        }
+        // the shortest edit script has 5 changes
+        let edits = get_text_edits("a\nb\nc\na\nb\nb\na\n", "c\nb\na\nb\na\nc\n");
+        assert_eq!(5, edits.iter().filter(|edit| **edit != CHANGE::NOP).count());
+    }
+
+    #[test]
+    fn patience_test() {
+        let old = split_lines("a\nx\nb\nx\nc\n");
+        let new = split_lines("b\nx\na\nx\nc\n");
+        // x is no anchor, only one of a and b
+        // the equal x c at the end is skipped before the anchors
+        assert_eq!(vec![(0, 2), (4, 4)], get_patience_anchors(&old, &new));
+        assert_eq!(
+            vec![
+                CHANGE::ADD,
+                CHANGE::ADD,
+                CHANGE::NOP,
+                CHANGE::REM,
+                CHANGE::REM,
+                CHANGE::NOP,
+                CHANGE::NOP,
+            ],
+            get_edits(&old, &new, ALGORITHM::PATIENCE)
+        );
+        assert!(get_patience_anchors(&split_lines("x\nx\n"), &split_lines("x\n")).is_empty());
+    }
+
+    #[test]
+    fn histogram_test() {
+        let old = split_lines("x\ny\nx\nz\n");
+        let new = split_lines("z\nx\ny\n");
+        // x y is longer than z and has a line as rare as z
+        assert_eq!(Some((0, 1, 2)), get_histogram_block(&old, &new));
+        assert_eq!(
+            vec![
+                CHANGE::ADD,
+                CHANGE::NOP,
+                CHANGE::NOP,
+                CHANGE::REM,
+                CHANGE::REM,
+            ],
+            get_edits(&old, &new, ALGORITHM::HISTOGRAM)
+        );
+        assert_eq!(
+            None,
+            get_histogram_block(&split_lines("a\n"), &split_lines("b\n"))
+        );
+    }
+
+    #[test]
+    fn diff_paths_test() {
+        let dir = std::env::temp_dir().join(format!("diff-rs-test-{}", std::process::id()));
+        let (old, new) = (dir.join("old"), dir.join("new"));
+        fs::create_dir_all(&old).unwrap();
+        fs::create_dir_all(new.join("sub")).unwrap();
+        fs::write(old.join("a.txt"), "1\n2\n").unwrap();
+        fs::write(new.join("a.txt"), "1\n3\n").unwrap();
+        fs::write(old.join("equal.txt"), "1\n").unwrap();
+        fs::write(new.join("equal.txt"), "1\n").unwrap();
+        fs::write(old.join("gone.txt"), "1\n").unwrap();
+        fs::write(new.join("image.bin"), [0, 1, 2]).unwrap();
+        fs::write(new.join("sub").join("added.txt"), "1\n").unwrap();
+
+        let files = diff_paths(&old, &new, &DiffOptions::default());
+        let single = diff_paths(&old.join("a.txt"), &new, &DiffOptions::default());
+        let missing = diff_paths(&old.join("missing.txt"), &new, &DiffOptions::default());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let files = files.unwrap();
+        let names: Vec<&str> = files.iter().map(|file| file.filename.as_str()).collect();
+        assert_eq!(
+            vec!["a.txt", "gone.txt", "image.bin", "sub/added.txt"],
+            names
+        );
+        assert_eq!(MODIFIER::MODIFIED, files[0].modifier);
+        assert_eq!((1, 2, 1, 2), files[0].hunks[0].line_info);
+        assert_eq!(MODIFIER::DELETE, files[1].modifier);
+        assert_eq!(
+            Some(Binary {
+                old_size: None,
+                new_size: Some(BINARY_SIZE::LITERAL(3)),
+            }),
+            files[2].binary
+        );
+        assert_eq!(MODIFIER::ADD, files[3].modifier);
+
+        // a file is compared with the file of the same name in the directory
+        let single = single.unwrap();
+        assert_eq!(1, single.len());
+        assert!(single[0].filename.ends_with("a.txt"));
+        assert!(single[0].old_filename.as_ref().unwrap().ends_with("a.txt"));
+
+        assert!(missing.unwrap_err().to_string().contains("missing.txt"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn diff_paths_symlinks_test() {
+        use std::os::unix::fs::symlink;
+
+        let dir = std::env::temp_dir().join(format!("diff-rs-symlinks-{}", std::process::id()));
+        let (old, new) = (dir.join("old"), dir.join("new"));
+        fs::create_dir_all(&old).unwrap();
+        fs::create_dir_all(&new).unwrap();
+        // broken symlinks are compared by their targets
+        symlink("missing.txt", old.join("broken")).unwrap();
+        symlink("missing.txt", new.join("broken")).unwrap();
+        symlink("missing.txt", old.join("gone")).unwrap();
+        symlink("old.txt", old.join("link")).unwrap();
+        symlink("new.txt", new.join("link")).unwrap();
+        fs::write(old.join("type.txt"), "1\n").unwrap();
+        symlink("type.txt", new.join("type.txt")).unwrap();
+
+        let files = diff_paths(&old, &new, &DiffOptions::default());
+        let fifo = std::process::Command::new("mkfifo")
+            .arg(old.join("fifo"))
+            .status()
+            .map(|status| status.success());
+        let fifo_files = diff_paths(&old, &new, &DiffOptions::default());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let files = files.unwrap();
+        let changes: Vec<(MODIFIER, &str)> = files
+            .iter()
+            .map(|file| (file.modifier, file.filename.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                (MODIFIER::DELETE, "gone"),
+                (MODIFIER::MODIFIED, "link"),
+                (MODIFIER::DELETE, "type.txt"),
+                (MODIFIER::ADD, "type.txt"),
+            ],
+            changes
+        );
+        assert_eq!(
+            vec![
+                LINE::REM {
+                    number: 1,
+                    line: "old.txt".into(),
+                    no_newline: true,
+                },
+                LINE::ADD {
+                    number: 1,
+                    line: "new.txt".into(),
+                    no_newline: true,
+                },
+            ],
+            files[1].hunks[0].content
+        );
+
+        // a pipe isn't read, it would block
+        if let Ok(true) = fifo {
+            let error = fifo_files.unwrap_err().to_string();
+            assert!(error.contains("fifo"), "{}", error);
+        }
+    }
+}
@@ -2,75 +2,200 @@
 //! import structs to define the different parts of a diff
 //!
 //! Main struct is `File` with the git modifier (add, delete, rename, ...),
-//! the commit id, filename and there hunks.
+//! the commit id, filename and there hunks. Renamed and copied files keep
+//! their old filename and the similarity index, files with a changed mode
+//! the old and the new mode and files of a `diff -u` their timestamps. A
+//! changed binary file has no hunks, but the sizes of the binary patch
+//! (`Binary`).
 //!
 //! A `Hunk` contains the lines (`Line`) with there diffs and also their
-//! modifieres.
+//! modifieres, the line numbers of the hunk header and the section heading
+//! after it.
 //!
-//! The lines (`Line`) consist of their numbers, modifiers and the linecontent.
+//! The input is split into sections (`SECTION`), the parsed files, the
+//! commits of a `git log -p` or `git show` with their files (`Commit`) and
+//! the text which isn't part of a diff.
+//!
+//! The lines (`Line`) consist of their numbers, modifiers and the linecontent
+//! and if the line has no newline at the end of the file. The lines of a
+//! combined diff of a merge have the change (`CHANGE`) to every parent.
+
+#![allow(clippy::upper_case_acronyms, non_camel_case_types)]
 
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
 pub enum MODIFIER {
     ADD,
     MODIFIED,
     RENAMED,
+    COPIED,
     DELETE,
 }
 
+/// The change of a line, for a combined diff the change to one parent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CHANGE {
+    ADD,
+    REM,
+    NOP,
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum LINE {
     ADD {
         number: usize,
         line: String,
+        no_newline: bool,
     },
     REM {
         number: usize,
         line: String,
+        no_newline: bool,
     },
     NOP {
         number_left: usize,
         number_right: usize,
         line: String,
+        no_newline: bool,
+    },
+    /// a line of a combined diff, the number is the line in the result or
+    /// in the first parent for a removed line
+    COMBINED {
+        number: usize,
+        parents: Vec<CHANGE>,
+        line: String,
+        no_newline: bool,
     },
 }
 
+impl LINE {
+    /// Returns the change of the line, a line of a combined diff is removed
+    /// if it is removed from any parent and added if it is added to any
+    /// parent
+    pub fn get_change(&self) -> CHANGE {
+        match self {
+            LINE::ADD { .. } => CHANGE::ADD,
+            LINE::REM { .. } => CHANGE::REM,
+            LINE::NOP { .. } => CHANGE::NOP,
+            LINE::COMBINED { parents, .. } => {
+                if parents.contains(&CHANGE::REM) {
+                    CHANGE::REM
+                } else if parents.contains(&CHANGE::ADD) {
+                    CHANGE::ADD
+                } else {
+                    CHANGE::NOP
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct Hunk {
+    /// start and count of the lines on the left and the right side
+    pub line_info: (usize, usize, usize, usize),
+    /// the enclosing function or section after the hunk header
+    pub heading: String,
     pub content: Vec<LINE>,
 }
 
 impl Hunk {
-    pub fn new(content: Vec<LINE>) -> Hunk {
-        Hunk { content: content }
+    pub fn new(
+        line_info: (usize, usize, usize, usize),
+        heading: String,
+        content: Vec<LINE>,
+    ) -> Hunk {
+        Hunk {
+            line_info,
+            heading,
+            content,
+        }
     }
 }
 
 impl fmt::Display for Hunk {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Content: {:?}", self.content)
+        write!(
+            f,
+            "@@ -{},{} +{},{} @@ {}\nContent: {:?}",
+            self.line_info.0,
+            self.line_info.1,
+            self.line_info.2,
+            self.line_info.3,
+            self.heading,
+            self.content
+        )
     }
 }
 
+/// The size of one side of a `GIT binary patch`, the full size of the file
+/// for a literal or the size of the delta data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BINARY_SIZE {
+    LITERAL(usize),
+    DELTA(usize),
+}
+
+/// A changed binary file, the sizes are only known from a `GIT binary patch`
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Binary {
+    pub old_size: Option<BINARY_SIZE>,
+    pub new_size: Option<BINARY_SIZE>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct File {
     pub modifier: MODIFIER,
     pub filename: String,
+    /// the filename before a rename or copy
+    pub old_filename: Option<String>,
+    /// the similarity index of a rename or copy in percent
+    pub similarity: Option<usize>,
+    /// the file mode before and after a mode change
+    pub old_mode: Option<String>,
+    pub new_mode: Option<String>,
+    /// the modification times of the old and the new file in a `diff -u`
+    pub old_timestamp: Option<String>,
+    pub new_timestamp: Option<String>,
     pub commit_id: String,
     pub hunks: Vec<Hunk>,
+    pub binary: Option<Binary>,
 }
 
 impl File {
     pub fn new(modifier: MODIFIER, filename: String, commit_id: String, hunks: Vec<Hunk>) -> File {
         File {
-            modifier: modifier,
-            filename: filename,
-            commit_id: commit_id,
-            hunks: hunks,
+            modifier,
+            filename,
+            old_filename: None,
+            similarity: None,
+            old_mode: None,
+            new_mode: None,
+            old_timestamp: None,
+            new_timestamp: None,
+            commit_id,
+            hunks,
+            binary: None,
         }
     }
 
+    /// Checks if the file is from a combined diff of a merge
+    pub fn is_combined(&self) -> bool {
+        self.hunks.iter().any(|hunk| {
+            hunk.content
+                .iter()
+                .any(|line| matches!(line, LINE::COMBINED { .. }))
+        })
+    }
+
+    /// Returns the highest line number in the hunks of the file or `0` for a
+    /// file without hunks or lines, e.g. a binary file or a pure rename
     pub fn get_max_line_number_size(&self) -> usize {
         self.hunks
             .iter()
@@ -78,18 +203,13 @@ impl File {
                 hunk.content
                     .iter()
                     .map(|line| match line {
-                        LINE::ADD {
-                            number: nr,
-                            line: _,
-                        } => nr,
-                        LINE::REM {
-                            number: nr,
-                            line: _,
-                        } => nr,
+                        LINE::ADD { number: nr, .. } => nr,
+                        LINE::REM { number: nr, .. } => nr,
+                        LINE::COMBINED { number: nr, .. } => nr,
                         LINE::NOP {
                             number_left: nr1,
                             number_right: nr2,
-                            line: _,
+                            ..
                         } => {
                             if nr1 > nr2 {
                                 nr1
@@ -99,23 +219,31 @@ impl File {
                         }
                     })
                     .max()
-                    .unwrap()
+                    .copied()
+                    .unwrap_or(0)
             })
             .max()
-            .unwrap()
-            .clone()
+            .unwrap_or(0)
     }
 }
 
 impl fmt::Display for File {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut hunk_str = String::new();
-        if self.hunks.len() > 0 {
+        if !self.hunks.is_empty() {
             hunk_str.push_str("Hunks: \n");
             self.hunks
                 .iter()
                 .for_each(|hunk| hunk_str.push_str(&format!("{}", hunk)));
         }
+        if let Some(old_filename) = &self.old_filename {
+            writeln!(f, "Old filename: {}", old_filename)?;
+        }
+        if let (Some(old_timestamp), Some(new_timestamp)) =
+            (&self.old_timestamp, &self.new_timestamp)
+        {
+            writeln!(f, "Timestamps: {} → {}", old_timestamp, new_timestamp)?;
+        }
         write!(
             f,
             "Filename: {}\nCommit-ID: {}\n\n{}",
@@ -123,3 +251,45 @@ impl fmt::Display for File {
         )
     }
 }
+
+/// A commit of `git log -p` or `git show` or a mail of `git format-patch`
+/// with the files of its diff
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Commit {
+    pub id: String,
+    /// the branches and tags after the commit id, e.g. `(HEAD -> main)`
+    pub refs: String,
+    pub author: String,
+    pub date: String,
+    /// the commit message without the indent of `git log`
+    pub message: String,
+    /// the number of the patch and the count of patches in a series of
+    /// `git format-patch`
+    pub series: Option<(usize, usize)>,
+    pub files: Vec<File>,
+}
+
+impl fmt::Display for Commit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Commit: {}\nAuthor: {}\nDate: {}\n\n{}\n",
+            self.id, self.author, self.date, self.message
+        )?;
+        self.files
+            .iter()
+            .try_for_each(|file| write!(f, "\n{}", file))
+    }
+}
+
+/// A part of the input, a parsed file, a commit with its files or text which
+/// is no diff and is shown unchanged
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+#[allow(clippy::large_enum_variant)]
+pub enum SECTION {
+    FILE(File),
+    COMMIT(Commit),
+    TEXT(String),
+}
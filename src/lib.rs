@@ -0,0 +1,71 @@
+//! A more beautiful and readable diff output. The library parses the output
+//! of `git diff`, `git log -p`, `git format-patch` and `diff` into files,
+//! hunks and lines and prints them in the style of the `diff-rs` command. It
+//! also compares two texts, files or directories itself.
+//!
+//! ```
+//! let input = "--- a.txt\n+++ b.txt\n@@ -1 +1 @@\n-apples\n+pears\n";
+//!
+//! let files = diff_rs::parse_strict(input).unwrap();
+//! assert_eq!("b.txt", files[0].filename);
+//!
+//! let mut options = diff_rs::PrintOptions::default();
+//! options.syntax = false;
+//! let mut output = Vec::new();
+//! let sections = diff_rs::parse_sections(input);
+//! diff_rs::print(&mut output, &sections, &options).unwrap();
+//! ```
+//!
+//! The parsers:
+//!
+//! * `parse_strict` returns the files of the input or a `ParseError` at the
+//!   first part which is no valid diff
+//! * `parse_sections` splits the input into files, commits and the text
+//!   between them, it never fails
+//! * `parse_content` returns only the files, also the files of the commits
+//! * `StreamParser` returns the sections of an input read line by line
+//!
+//! The `a/` and `b/` prefixes of the filenames of `git diff` are guessed, the
+//! prefixes of `--src-prefix` and `--dst-prefix` can be set in the
+//! `ParseOptions` of `parse_sections_with_options`,
+//! `parse_strict_with_options` and `StreamParser::with_options`.
+//!
+//! The sections are printed with `print` or a `Printer`, both are configured
+//! by the `PrintOptions`.
+//!
+//! `diff_texts` returns the hunks of two texts and `diff_paths` the files of
+//! two files or directories, with the algorithm (`ALGORITHM`) and the count
+//! of context lines of the `DiffOptions`.
+//!
+//! For tests `assert_diff!` and `assert_debug_diff!` compare two values like
+//! `assert_eq!`, but show the difference as a diff.
+//!
+//! # Stability
+//!
+//! The items exported here are the public API and follow semantic
+//! versioning, while the version is `0.x` a breaking change increases the
+//! minor version. The enums (except `CHANGE` and `BINARY_SIZE`) and the
+//! structs of the model, `ParseError`, `ParseOptions`, `PrintOptions`, `ALGORITHM` and
+//! `DiffOptions` are `#[non_exhaustive]`, new variants and fields are no
+//! breaking change. The escape sequences of the printed output are no part of
+//! the API.
+
+#[macro_use]
+extern crate nom;
+
+mod assert;
+mod diff;
+mod file;
+mod parser;
+mod printer;
+mod syntax;
+
+#[doc(hidden)]
+pub use crate::assert::assert_diff_failed;
+pub use crate::diff::{diff_paths, diff_texts, DiffOptions, ALGORITHM, DEFAULT_CONTEXT};
+pub use crate::file::{Binary, Commit, File, Hunk, BINARY_SIZE, CHANGE, LINE, MODIFIER, SECTION};
+pub use crate::parser::{
+    parse_content, parse_sections, parse_sections_with_options, parse_strict,
+    parse_strict_with_options, ParseError, ParseOptions, StreamParser,
+};
+pub use crate::printer::{print, PrintOptions, Printer};
@@ -1,16 +1,14 @@
 //! The initial point is to parse the arguments, if exists and fetch the stdin
-//! throw the parser to print the diff content in a beautiful way.
-
-mod file;
-mod parser;
-mod printer;
-
-#[macro_use]
-extern crate nom;
+//! throw the parser to print the diff content in a beautiful way. The input is
+//! read line by line and every section is printed as soon as it is complete.
+//! The parser and the printer are the ones of the `diff_rs` library. With two
+//! paths the files are compared by the diff of the library instead.
 
 use clap::{crate_authors, crate_description, crate_name, crate_version, App, Arg};
-use std::io::{self, Read};
-use strip_ansi_escapes;
+use diff_rs::{DiffOptions, ParseOptions, PrintOptions, Printer, StreamParser, ALGORITHM, SECTION};
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+use std::process;
 
 fn main() {
     // create cli app
@@ -25,15 +23,274 @@ fn main() {
                 .long("column")
                 .help("Show in 2 columnview"),
         )
+        .arg(
+            Arg::with_name("nosyntax")
+                .short("n")
+                .long("no-syntax")
+                .help("Disable the syntax highlighting"),
+        )
+        .arg(
+            Arg::with_name("strict")
+                .long("strict")
+                .help("Exit with an error at the first part of the input which is no valid diff"),
+        )
+        .arg(
+            Arg::with_name("srcprefix")
+                .long("src-prefix")
+                .value_name("PREFIX")
+                .takes_value(true)
+                .help("The prefix of the old filenames of git, like of git diff --src-prefix"),
+        )
+        .arg(
+            Arg::with_name("dstprefix")
+                .long("dst-prefix")
+                .value_name("PREFIX")
+                .takes_value(true)
+                .help("The prefix of the new filenames of git, like of git diff --dst-prefix"),
+        )
+        .arg(
+            Arg::with_name("noprefix")
+                .long("no-prefix")
+                .conflicts_with_all(&["srcprefix", "dstprefix"])
+                .help("The filenames of git have no prefixes, like of git diff --no-prefix"),
+        )
+        .arg(
+            Arg::with_name("old")
+                .index(1)
+                .requires("new")
+                .help("The old file or directory to compare instead of reading a diff"),
+        )
+        .arg(
+            Arg::with_name("new")
+                .index(2)
+                .help("The new file or directory to compare"),
+        )
+        .arg(
+            Arg::with_name("algorithm")
+                .long("algorithm")
+                .takes_value(true)
+                .possible_values(&["myers", "patience", "histogram"])
+                .default_value("myers")
+                .help("The diff algorithm to compare two paths"),
+        )
+        .arg(
+            Arg::with_name("unified")
+                .short("U")
+                .long("unified")
+                .value_name("LINES")
+                .takes_value(true)
+                .validator(|value| {
+                    value
+                        .parse::<usize>()
+                        .map(|_| ())
+                        .map_err(|_| String::from("the count of lines must be a number"))
+                })
+                .help("The count of context lines to compare two paths [default: 3]"),
+        )
         .get_matches();
 
-    let columnview = matches.value_of("columnview");
+    let mut options = PrintOptions::default();
+    options.columnview = matches.is_present("columnview");
+    options.syntax = !matches.is_present("nosyntax");
+    let strict = matches.is_present("strict");
+
+    // like git the other prefix is the default one
+    let mut parse_options = ParseOptions::default();
+    if matches.is_present("noprefix") {
+        parse_options.prefixes = Some((String::new(), String::new()));
+    } else if matches.is_present("srcprefix") || matches.is_present("dstprefix") {
+        parse_options.prefixes = Some((
+            matches.value_of("srcprefix").unwrap_or("a/").to_string(),
+            matches.value_of("dstprefix").unwrap_or("b/").to_string(),
+        ));
+    }
+
+    let printer = Printer::new(&options);
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+
+    if let (Some(old), Some(new)) = (matches.value_of("old"), matches.value_of("new")) {
+        let mut diff_options = DiffOptions::default();
+        diff_options.algorithm = match matches.value_of("algorithm") {
+            Some("patience") => ALGORITHM::PATIENCE,
+            Some("histogram") => ALGORITHM::HISTOGRAM,
+            _ => ALGORITHM::MYERS,
+        };
+        if let Some(context) = matches.value_of("unified") {
+            // checked by the validator
+            diff_options.context = context.parse().unwrap_or(diff_options.context);
+        }
+        print_paths(&printer, &mut output, old, new, &diff_options);
+        return;
+    }
+
+    let unparsed_lines = if strict {
+        print_strict(&printer, &mut output, &parse_options)
+    } else {
+        print_stream(&printer, &mut output, parse_options)
+    };
+    if unparsed_lines > 0 {
+        eprintln!(
+            "{}: warning: {} lines are not part of a diff and are shown unchanged",
+            crate_name!(),
+            unparsed_lines
+        );
+    }
+}
+
+/// Compares two files or directories and prints the changed files, exits with
+/// the error if a path can't be read
+///
+/// # Arguments
+///
+/// * `printer` - the printer with the output settings
+/// * `output` - the locked stdout
+/// * `old` - the path of the old file or directory
+/// * `new` - the path of the new file or directory
+/// * `diff_options` - the algorithm and the count of context lines
+///
+fn print_paths(
+    printer: &Printer,
+    output: &mut impl Write,
+    old: &str,
+    new: &str,
+    diff_options: &DiffOptions,
+) {
+    match diff_rs::diff_paths(Path::new(old), Path::new(new), diff_options) {
+        Ok(files) => {
+            for file in files {
+                // a closed pipe, e.g. a quit pager, is no error
+                if printer.print_section(output, &SECTION::FILE(file)).is_err() {
+                    return;
+                }
+            }
+            let _ = writeln!(output);
+        }
+        Err(e) => {
+            eprintln!("{}: error: {}", crate_name!(), e);
+            process::exit(1);
+        }
+    }
+}
 
-    let mut buffer = String::new();
-    io::stdin().read_to_string(&mut buffer).unwrap();
-    let plain_buffer = String::from_utf8(strip_ansi_escapes::strip(&buffer).unwrap()).unwrap();
+/// Reads the whole input and prints it only if it is a valid diff, otherwise
+/// exits with the error. The valid input is printed like a stream, with the
+/// banners of the commits.
+///
+/// # Arguments
+///
+/// * `printer` - the printer with the output settings
+/// * `output` - the locked stdout
+/// * `parse_options` - the prefixes of the filenames
+///
+fn print_strict(printer: &Printer, output: &mut impl Write, parse_options: &ParseOptions) -> usize {
+    let mut buffer = Vec::new();
+    if let Err(e) = io::stdin().read_to_end(&mut buffer) {
+        eprintln!("{}: error reading the input: {}", crate_name!(), e);
+        process::exit(1);
+    }
+    let plain_buffer = strip_colours(&buffer);
+    let plain_buffer = String::from_utf8_lossy(&plain_buffer);
+
+    if let Err(e) = diff_rs::parse_strict_with_options(&plain_buffer, parse_options) {
+        eprintln!("{}: error: {}", crate_name!(), e);
+        process::exit(1);
+    }
+    for section in diff_rs::parse_sections_with_options(&plain_buffer, parse_options) {
+        // a closed pipe, e.g. a quit pager, is no error
+        if printer.print_section(output, &section).is_err() {
+            return 0;
+        }
+    }
+    let _ = writeln!(output);
+    0
+}
+
+/// Reads the input line by line and prints every section as soon as it is
+/// complete. Returns the count of the lines which are no diff.
+///
+/// # Arguments
+///
+/// * `printer` - the printer with the output settings
+/// * `output` - the locked stdout
+/// * `parse_options` - the prefixes of the filenames
+///
+fn print_stream(printer: &Printer, output: &mut impl Write, parse_options: ParseOptions) -> usize {
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut stream_parser = StreamParser::with_options(parse_options);
+    let mut unparsed_lines = 0;
+    let mut line = Vec::new();
+
+    let mut print_sections = |sections: Vec<SECTION>| -> io::Result<()> {
+        for section in sections {
+            // the text sections are shown unchanged
+            if let SECTION::TEXT(text) = &section {
+                unparsed_lines += text.lines().count();
+            }
+            printer.print_section(output, &section)?;
+        }
+        Ok(())
+    };
+
+    loop {
+        line.clear();
+        match input.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("{}: error reading the input: {}", crate_name!(), e);
+                process::exit(1);
+            }
+        }
+        let plain_line = strip_colours(&line);
+        let sections = stream_parser.push_line(&String::from_utf8_lossy(&plain_line));
+        // a closed pipe, e.g. a quit pager, is no error
+        if print_sections(sections).is_err() {
+            return 0;
+        }
+    }
+    if print_sections(stream_parser.finish()).is_ok() {
+        let _ = writeln!(output);
+    }
+    unparsed_lines
+}
+
+/// Removes the colours and other escape sequences of the input. The tabs are
+/// kept, they separate the timestamps of a `diff -u` and are part of the line
+/// contents.
+///
+/// # Arguments
+///
+/// * `buffer` - the input with escape sequences
+///
+fn strip_colours(buffer: &[u8]) -> Vec<u8> {
+    let mut plain_buffer = Vec::with_capacity(buffer.len());
+    for (i, part) in buffer.split(|byte| *byte == b'\t').enumerate() {
+        if i > 0 {
+            plain_buffer.push(b'\t');
+        }
+        match strip_ansi_escapes::strip(part) {
+            Ok(plain_part) => plain_buffer.extend(plain_part),
+            Err(_) => plain_buffer.extend_from_slice(part),
+        }
+    }
+    plain_buffer
+}
 
-    let files: Vec<file::File> = parser::parse_content(&plain_buffer);
+/* --------------------------------------------------------- */
+/* ------------------------- TESTS ------------------------- */
+/* --------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!("{}", printer::print(&files, columnview));
+    #[test]
+    fn strip_colours_test() {
+        let input = "\u{1b}[32m+\tfn main() {\u{1b}[m\n--- a.txt\t2019-03-04\n";
+        assert_eq!(
+            "+\tfn main() {\n--- a.txt\t2019-03-04\n".as_bytes(),
+            &strip_colours(input.as_bytes())[..]
+        );
+    }
 }
@@ -1,12 +1,78 @@
 //! Preprocess the input to convert it to raw structures
+//!
+//! Besides the output of `git diff` the unified (`diff -u`), the context
+//! (`diff -c`) and the normal format (`diff`) are read, all of them are
+//! converted to the same raw structures.
+//!
+//! The commit headers of `git log -p` and `git show` and the mail headers of
+//! `git format-patch` are read as well, the files after them are grouped to
+//! the commit.
+//!
+//! Parts of the input which are no diff are kept as text sections, so they
+//! can be shown unchanged. The strict parser instead stops at the first of
+//! them with a `ParseError`.
 
-use crate::file::{File, Hunk, LINE, MODIFIER};
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::file::{Binary, Commit, File, Hunk, BINARY_SIZE, CHANGE, LINE, MODIFIER, SECTION};
+
+// the first line of a file section, a diff command or the old path of a
+// unified or context diff followed by the new path
+const FILE_START_COMMAND: &str = "diff ";
+const FILE_START_OLD_PATH: &str = "--- ";
+const FILE_START_NEW_PATH: &str = "+++ ";
+const FILE_START_CONTEXT_OLD_PATH: &str = "*** ";
+// the end of the old line range of a context hunk, "*** 1,5 ****"
+const CONTEXT_RANGE_OLD_END: &str = " ****";
+// the path of a missing file in a unified diff
+const DEV_NULL: &str = "/dev/null";
+// the timestamps of a missing file with `diff -N` in the unified and the
+// context format
+const EPOCH_TIMESTAMPS: [&str; 2] = ["1970-01-01 00:00:00", "Thu Jan  1 00:00:00 1970"];
+// the first line of a commit of `git log -p` or `git show`
+const COMMIT_START: &str = "commit ";
+// the indent of the commit message
+const COMMIT_MESSAGE_INDENT: &str = "    ";
+// the first line of a mail of `git format-patch`, "From <commit id> <date>"
+const PATCH_START: &str = "From ";
+// the line between the message and the diffstat of a mail
+const PATCH_MESSAGE_END: &str = "---";
+// the start of the signature after the last file of a mail, "-- \n2.39.2\n"
+const PATCH_SIGNATURE: &str = "-- \n";
+// the lines which can start a file or a commit
+const SECTION_START_PREFIXES: [&str; 6] = [
+    FILE_START_COMMAND,
+    FILE_START_OLD_PATH,
+    FILE_START_NEW_PATH,
+    FILE_START_CONTEXT_OLD_PATH,
+    COMMIT_START,
+    PATCH_START,
+];
+// the lines from a possible start of a file or a commit on which are needed
+// to know it, e.g. the `+++` line or the fields of a commit
+const SECTION_START_LINES: usize = 3;
 
 #[derive(Debug, PartialEq)]
 enum RawLine<'a> {
     Left(&'a str),
     Right(&'a str),
     Both(&'a str),
+    /// the changes to every parent of a combined diff and the content
+    Combined(&'a str, &'a str),
+    NoNewline,
+}
+
+/// A line of the old or the new lines of a context hunk
+#[derive(Debug, PartialEq)]
+enum RawContextLine<'a> {
+    /// an unchanged line, "  "
+    Both(&'a str),
+    /// a line of a changed block, "! "
+    Changed(&'a str),
+    /// a removed line in the old ("- ") or an added line in the new lines ("+ ")
+    Single(&'a str),
+    NoNewline,
 }
 
 #[derive(Debug, PartialEq)]
@@ -22,24 +88,127 @@ enum ExtendedHeader<'a> {
     Index(&'a str),
 }
 
+/// The path of a `---` or `+++` line with the timestamp of `diff -u`
+#[derive(Debug, PartialEq)]
+struct RawPath<'a> {
+    path: &'a str,
+    timestamp: Option<&'a str>,
+}
+
 #[derive(Debug, PartialEq)]
 struct RawHeader<'a> {
-    filenames: (&'a str, &'a str),
+    /// the names without the quotes and escapes of git, but with the `a/`
+    /// and `b/` prefixes of `diff --git`
+    filenames: (Cow<'a, str>, Cow<'a, str>),
+    /// the lengths of the prefixes of the names of `diff --git` which are
+    /// guessed from the names, the other names have no prefixes
+    prefixes: Option<(usize, usize)>,
     extended_headers: Vec<ExtendedHeader<'a>>,
+    /// the old and the new path of the `---` and `+++` lines
+    paths: Option<(RawPath<'a>, RawPath<'a>)>,
+    /// the header starts with `diff --git` or `diff --cc`, the filenames
+    /// are from that line and not from the paths
+    git: bool,
 }
 
 #[derive(Debug, PartialEq)]
 struct RawHunk<'a> {
+    /// the first line of the hunk in the input without the newline, for the
+    /// line of an error
+    header: &'a str,
     line_info: (u32, u32, u32, u32),
+    heading: &'a str,
+    /// the line ranges of all parents of a combined diff, empty otherwise
+    parents: Vec<(u32, u32)>,
     lines: Vec<RawLine<'a>>,
 }
 
+#[derive(Debug, PartialEq)]
+enum RawBinary<'a> {
+    Differ,
+    Patch(Vec<(&'a str, u32)>),
+}
+
+/// The header of a commit, the fields like `Author` and `Date` and the lines
+/// of the message
+#[derive(Debug, PartialEq)]
+struct RawCommit<'a> {
+    id: &'a str,
+    refs: &'a str,
+    fields: Vec<(&'a str, &'a str)>,
+    message: Vec<&'a str>,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct RawFile<'a> {
     header: RawHeader<'a>,
+    binary: Option<RawBinary<'a>>,
     hunks: Vec<RawHunk<'a>>,
 }
 
+/// A part of the input, the signature of a mail is skipped
+#[derive(Debug, PartialEq)]
+enum RawSection<'a> {
+    Commit(RawCommit<'a>),
+    File(RawFile<'a>),
+    Signature,
+    Text(&'a str),
+}
+
+/// The first part of the input which is no valid diff, the lines and columns
+/// start at 1
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// a line before the first file section
+    NoDiff { line: usize },
+    /// a file header without valid file names
+    Header { line: usize, column: usize },
+    /// a line in the section of `file` which isn't the `expected` part
+    Unexpected {
+        line: usize,
+        file: String,
+        expected: &'static str,
+    },
+    /// a hunk with another count of removed and added lines than in its header
+    LineCount {
+        line: usize,
+        file: String,
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::NoDiff { line } => {
+                write!(f, "line {}: expected file header", line)
+            }
+            ParseError::Header { line, column } => {
+                write!(f, "line {}, column {}: expected file names", line, column)
+            }
+            ParseError::Unexpected {
+                line,
+                file,
+                expected,
+            } => write!(f, "line {} in {}: expected {}", line, file, expected),
+            ParseError::LineCount {
+                line,
+                file,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {} in {}: expected hunk with {} old and {} new lines, found {} and {}",
+                line, file, expected.0, expected.1, found.0, found.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 #[allow(dead_code)]
 fn is_space(c: char) -> bool {
     nom::is_space(c as u8)
@@ -53,12 +222,126 @@ fn is_whitespace(c: char) -> bool {
     is_space(c) || is_new_line(c)
 }
 
-// "diff --git a/script.sh b/script.sh\n"
-named!(parse_filename(&str) -> (&str, &str), do_parse!(
-        opt!(take_until_and_consume!("diff --git a/")) >>
-        filename_a: take_until_and_consume!(" b/") >>
-        filename_b: take_until_and_consume!("\n") >>
-        ((filename_a, filename_b))
+// "Author: Jane Doe <jane@example.com>\n"
+named!(parse_commit_field(&str) -> (&str, &str), do_parse!(
+        key: take_while1!(|c: char| c.is_ascii_alphabetic()) >>
+        tag!(":") >>
+        value: take_until_and_consume!("\n") >>
+        ((key, value.trim()))
+));
+
+/// Parses the commit message, the indented and the empty lines
+///
+/// # Arguments
+///
+/// * `input` - the lines after the fields of the commit
+///
+fn parse_commit_message(input: &str) -> nom::IResult<&str, Vec<&str>> {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut remaining = input;
+
+    while !remaining.is_empty() {
+        let line_end = remaining.find('\n').map_or(remaining.len(), |i| i + 1);
+        let line = remaining[..line_end].trim_end_matches('\n');
+        if line.trim().is_empty() {
+            lines.push("");
+        } else if let Some(message_line) = line.strip_prefix(COMMIT_MESSAGE_INDENT) {
+            lines.push(message_line);
+        } else {
+            break;
+        }
+        remaining = &remaining[line_end..];
+    }
+    Ok((remaining, lines))
+}
+
+// "commit 4644f8d… (HEAD -> main)\nAuthor: …\nDate: …\n\n    message\n\n",
+// with the empty line after the diff of the previous commit
+named!(parse_log_commit(&str) -> RawCommit<'_>, do_parse!(
+        many0!(tag!("\n")) >>
+        tag!(COMMIT_START) >>
+        id: take_while1!(|c: char| c.is_ascii_hexdigit()) >>
+        refs: take_until_and_consume!("\n") >>
+        fields: many1!(complete!(parse_commit_field)) >>
+        message: parse_commit_message >>
+        (RawCommit { id, refs: refs.trim(), fields, message })
+));
+
+// "Subject: [PATCH 2/5] Replace the pears\n" with the folded lines of a
+// long value
+named!(parse_patch_field(&str) -> (&str, &str), do_parse!(
+        key: take_while1!(|c: char| c.is_ascii_alphanumeric() || c == '-') >>
+        tag!(":") >>
+        value: recognize!(pair!(
+            take_until_and_consume!("\n"),
+            many0!(complete!(preceded!(one_of!(" \t"), take_until_and_consume!("\n"))))
+        )) >>
+        ((key, value.trim()))
+));
+
+/// Parses the message of a mail up to the `---` line and skips the diffstat
+/// after it
+///
+/// # Arguments
+///
+/// * `input` - the lines after the mail headers
+///
+fn parse_patch_message(input: &str) -> nom::IResult<&str, Vec<&str>> {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut remaining = input;
+
+    while !remaining.is_empty() && !is_file_start(remaining) {
+        let line_end = remaining.find('\n').map_or(remaining.len(), |i| i + 1);
+        let line = remaining[..line_end].trim_end_matches('\n');
+        remaining = &remaining[line_end..];
+        if line == PATCH_MESSAGE_END {
+            // the diffstat up to the first file
+            while !remaining.is_empty()
+                && !is_file_start(remaining)
+                && !is_patch_signature(remaining)
+            {
+                let line_end = remaining.find('\n').map_or(remaining.len(), |i| i + 1);
+                remaining = &remaining[line_end..];
+            }
+            break;
+        }
+        lines.push(line);
+    }
+    Ok((remaining, lines))
+}
+
+// "From 4644f8d… Mon Sep 17 00:00:00 2001\nFrom: …\nDate: …\nSubject: …\n\n"
+// with the message and the diffstat of `git format-patch`
+named!(parse_patch(&str) -> RawCommit<'_>, do_parse!(
+        many0!(tag!("\n")) >>
+        tag!(PATCH_START) >>
+        id: take_while1!(|c: char| c.is_ascii_hexdigit()) >>
+        take_until_and_consume!("\n") >>
+        fields: many1!(complete!(parse_patch_field)) >>
+        tag!("\n") >>
+        message: parse_patch_message >>
+        (RawCommit { id, refs: "", fields, message })
+));
+
+named!(parse_commit(&str) -> RawCommit<'_>, alt!(parse_log_commit | parse_patch));
+
+// the first line and the first field of a commit or a mail, the rest of the
+// header and the message may still follow
+named!(parse_commit_header_start(&str) -> &str, do_parse!(
+        many0!(tag!("\n")) >>
+        alt!(tag!(COMMIT_START) | tag!(PATCH_START)) >>
+        id: take_while1!(|c: char| c.is_ascii_hexdigit()) >>
+        take_until_and_consume!("\n") >>
+        alt!(parse_commit_field | parse_patch_field) >>
+        (id)
+));
+
+// "-- \n2.39.2\n\n" after the last file of a mail
+named!(parse_patch_signature(&str) -> &str, do_parse!(
+        tag!(PATCH_SIGNATURE) >>
+        version: take_until_and_consume!("\n") >>
+        many0!(complete!(tag!("\n"))) >>
+        (version)
 ));
 
 named!(parse_extended_header_mode(&str) -> ExtendedHeader<'_>, do_parse!(
@@ -69,6 +352,14 @@ named!(parse_extended_header_mode(&str) -> ExtendedHeader<'_>, do_parse!(
         (ExtendedHeader::ChMode((old_mode, new_mode)))
 ));
 
+// "mode 100644,100644..100755\n" of a combined diff
+named!(parse_extended_header_combined_mode(&str) -> ExtendedHeader<'_>, do_parse!(
+        tag!("mode ") >>
+        old_modes: take_until_and_consume!("..") >>
+        new_mode: take_until_and_consume!("\n") >>
+        (ExtendedHeader::ChMode((old_modes, new_mode)))
+));
+
 named!(parse_extended_header_deleted(&str) -> ExtendedHeader<'_>, do_parse!(
         tag!("deleted") >> take_until_and_consume!("\n") >> (ExtendedHeader::Deleted)
 ));
@@ -115,44 +406,134 @@ named!(parse_extended_header_index(&str) -> ExtendedHeader<'_>, do_parse!(
 
 named!(parse_extended_header(&str) -> ExtendedHeader<'_>, do_parse!(
         extended_header: alt!(
-            parse_extended_header_mode | parse_extended_header_deleted |
+            parse_extended_header_mode | parse_extended_header_combined_mode |
+            parse_extended_header_deleted |
             parse_extended_header_new_file | parse_extended_header_copy_file |
             parse_extended_header_rename_file | parse_extended_header_similarity_index |
             parse_extended_header_dissimilarity_index | parse_extended_header_index ) >>
         (extended_header)));
 
-named!(parse_raw_file_header(&str) -> RawHeader<'_>, do_parse!(
-        filenames: parse_filename >>
+// "diff --cc file.txt\n" or "diff --combined file.txt\n" of a merge
+named!(parse_combined_filename(&str) -> (Cow<'_, str>, Cow<'_, str>), do_parse!(
+        alt!(tag!("diff --cc ") | tag!("diff --combined ")) >>
+        filename: take_until_and_consume!("\n") >>
+        ((unquote_path(filename), unquote_path(filename)))
+));
+
+// the "--- a/script.sh\n" and "+++ b/script.sh\n" lines after the extended
+// headers of git
+named!(parse_git_paths(&str) -> (RawPath<'_>, RawPath<'_>), do_parse!(
+        old: parse_path_old >>
+        new: parse_path_new >>
+        ((old, new))
+));
+
+// the file names of the "diff --git" line are split with the help of the
+// paths and the rename and copy headers
+named!(parse_git_file_header(&str) -> RawHeader<'_>, do_parse!(
+        tag!("diff --git ") >>
+        names: take_until_and_consume!("\n") >>
+        extended_headers: many0!(complete!(parse_extended_header)) >>
+        paths: opt!(complete!(parse_git_paths)) >>
+        filenames: expr_opt!(get_git_filenames(names, paths.as_ref(), &extended_headers)) >>
+        prefixes: value!(get_git_prefixes(&filenames.0, &filenames.1)) >>
+        (RawHeader {
+            filenames,
+            prefixes: Some(prefixes),
+            extended_headers,
+            paths,
+            git: true
+        })
+));
+
+named!(parse_combined_file_header(&str) -> RawHeader<'_>, do_parse!(
+        filenames: parse_combined_filename >>
         extended_headers: many0!(complete!(parse_extended_header)) >>
+        paths: opt!(complete!(parse_git_paths)) >>
         (RawHeader {
             filenames,
-            extended_headers
+            prefixes: None,
+            extended_headers,
+            paths,
+            git: true
         })
 ));
 
-named!(parse_file_names_after_extended_header(&str) -> (), do_parse!(
-        tag!("--- ") >> take_until_and_consume!("\n") >>
-        tag!("+++ ") >> take_until_and_consume!("\n") >>
-        ()
+named!(parse_raw_file_header(&str) -> RawHeader<'_>, alt!(
+        parse_git_file_header | parse_combined_file_header
+));
+
+// "diff -ruN dir1/list.txt dir2/list.txt\n", the header of git is no diff
+// command
+named!(parse_diff_command(&str) -> &str, do_parse!(
+        not!(tag!("diff --git ")) >>
+        tag!(FILE_START_COMMAND) >>
+        command: take_until_and_consume!("\n") >>
+        (command)
+));
+
+// "--- dir1/list.txt\t2019-03-04 10:21:31.000000000 +0100\n"
+named!(parse_path_old(&str) -> RawPath<'_>, do_parse!(
+        tag!(FILE_START_OLD_PATH) >>
+        path: map!(take_until_and_consume!("\n"), get_raw_path) >>
+        (path)
+));
+
+// "+++ dir2/list.txt\t2019-03-04 10:25:12.000000000 +0100\n"
+named!(parse_path_new(&str) -> RawPath<'_>, do_parse!(
+        tag!(FILE_START_NEW_PATH) >>
+        path: map!(take_until_and_consume!("\n"), get_raw_path) >>
+        (path)
 ));
 
-named!(parse_u32(&str) -> Result<u32, std::num::ParseIntError>,
-    map!(nom::digit, std::str::FromStr::from_str)
+// the header of `diff -u` or `diff -ruN`, optional with the diff command
+named!(parse_unified_file_header(&str) -> RawHeader<'_>, do_parse!(
+        opt!(complete!(parse_diff_command)) >>
+        old: parse_path_old >>
+        new: parse_path_new >>
+        (RawHeader {
+            filenames: (old.path.into(), new.path.into()),
+            prefixes: None,
+            extended_headers: Vec::new(),
+            paths: Some((old, new)),
+            git: false
+        })
+));
+
+/// Splits the path and the optional timestamp after a tab
+fn get_raw_path(line: &str) -> RawPath<'_> {
+    match line.split_once('\t') {
+        Some((path, timestamp)) => RawPath {
+            path,
+            timestamp: Some(timestamp.trim()).filter(|timestamp| !timestamp.is_empty()),
+        },
+        None => RawPath {
+            path: line.trim_end(),
+            timestamp: None,
+        },
+    }
+}
+
+named!(parse_u32(&str) -> u32,
+    map_res!(nom::digit, std::str::FromStr::from_str)
 );
 
-// "@@ -1,3 +1,3 @@\n";
-named!(parse_lines_info(&str) -> (u32, u32, u32, u32), do_parse!(
-        opt!(parse_file_names_after_extended_header) >>
+// "1,3" or "1" if the count is 1
+named!(parse_line_range(&str) -> (u32, u32), do_parse!(
+        start_line: parse_u32 >>
+        lines: opt!(preceded!(tag!(","), parse_u32)) >>
+        (start_line, lines.unwrap_or(1))
+));
+
+// "@@ -1,3 +1,3 @@ fn main() {\n";
+named!(parse_lines_info(&str) -> ((u32, u32, u32, u32), &str), do_parse!(
         tag!("@@ -") >>
-        start_line_left: parse_u32 >>
-        tag!(",") >>
-        lines_left: parse_u32 >>
+        left: parse_line_range >>
         tag!(" +") >>
-        start_line_right: parse_u32 >>
-        tag!(",") >>
-        lines_right: parse_u32 >>
-        take_until_and_consume!("\n") >>
-        (start_line_left.unwrap(), lines_left.unwrap(), start_line_right.unwrap(), lines_right.unwrap())
+        right: parse_line_range >>
+        tag!(" @@") >>
+        heading: take_until_and_consume!("\n") >>
+        ((left.0, left.1, right.0, right.1), heading.trim())
 ));
 
 named!(parse_line_both(&str) -> RawLine<'_>, do_parse!(
@@ -173,350 +554,1902 @@ named!(parse_line_right(&str) -> RawLine<'_>, do_parse!(
         (RawLine::Right(content))
 ));
 
+// "\\ No newline at end of file\n"
+named!(parse_line_no_newline(&str) -> RawLine<'_>, do_parse!(
+        tag!("\\") >>
+        take_till!(is_new_line) >>
+        (RawLine::NoNewline)
+));
+
 named!(parse_line(&str) -> RawLine<'_>, do_parse!(
-        line: alt!(parse_line_both | parse_line_left | parse_line_right) >>
+        line: alt!(parse_line_both | parse_line_left | parse_line_right | parse_line_no_newline) >>
         opt!(alt!(tag!("\n") | eof!())) >>
         (line)
 ));
 
-named!(parse_lines(&str) -> Vec<RawLine<'_>>,
-       many0!(complete!(parse_line))
-);
+/// Parses the lines of a hunk. After the line counts of the hunk header the
+/// lines end at the start of the next file, so `--- ` and `+++ ` lines of a
+/// unified diff aren't taken as removed and added lines.
+///
+/// # Arguments
+///
+/// * `input` - the lines after the hunk header
+/// * `counts` - the count of the old and the new lines of the hunk
+///
+fn parse_hunk_lines(input: &str, counts: (u32, u32)) -> nom::IResult<&str, Vec<RawLine<'_>>> {
+    let mut lines: Vec<RawLine<'_>> = Vec::new();
+    let (mut left, mut right) = counts;
+    let mut remaining = input;
+
+    while !(left == 0 && right == 0 && (is_file_start(remaining) || is_patch_signature(remaining)))
+    {
+        match parse_line(remaining) {
+            Ok((rest, line)) if rest.len() < remaining.len() => {
+                match line {
+                    RawLine::Left(_) => left = left.saturating_sub(1),
+                    RawLine::Right(_) => right = right.saturating_sub(1),
+                    RawLine::Both(_) => {
+                        left = left.saturating_sub(1);
+                        right = right.saturating_sub(1);
+                    }
+                    RawLine::Combined(..) | RawLine::NoNewline => {}
+                }
+                lines.push(line);
+                remaining = rest;
+            }
+            _ => break,
+        }
+    }
+    Ok((remaining, lines))
+}
 
 named!(parse_raw_file_hunk(&str) -> RawHunk<'_>, do_parse!(
-        line_info: parse_lines_info >>
-        lines: parse_lines >>
+        header: peek!(take_till!(is_new_line)) >>
+        lines_info: parse_lines_info >>
+        lines: call!(parse_hunk_lines, ((lines_info.0).1, (lines_info.0).3)) >>
+        (RawHunk {
+            header,
+            line_info: lines_info.0,
+            heading: lines_info.1,
+            parents: Vec::new(),
+            lines
+        })
+));
+
+// "@@@ -1,5 -1,4 +1,7 @@@ fn main() {\n" with an `@` more than parents
+named!(parse_combined_lines_info(&str) -> (Vec<(u32, u32)>, (u32, u32), &str), do_parse!(
+        markers: take_while1!(|c| c == '@') >>
+        tag!(" ") >>
+        parents: many1!(terminated!(preceded!(tag!("-"), parse_line_range), tag!(" "))) >>
+        tag!("+") >>
+        result: parse_line_range >>
+        tag!(" ") >>
+        tag!(markers) >>
+        heading: take_until_and_consume!("\n") >>
+        (parents, result, heading.trim())
+));
+
+/// Parses the lines of a combined hunk, every line starts with a change
+/// marker (` `, `+` or `-`) for every parent
+///
+/// # Arguments
+///
+/// * `input` - the lines after the hunk header
+/// * `parents` - the count of the parents
+///
+fn parse_combined_lines(input: &str, parents: usize) -> nom::IResult<&str, Vec<RawLine<'_>>> {
+    let mut lines: Vec<RawLine<'_>> = Vec::new();
+    let mut remaining = input;
+
+    while !remaining.is_empty() {
+        let line_end = remaining.find('\n').map_or(remaining.len(), |i| i + 1);
+        let line = remaining[..line_end].trim_end_matches('\n');
+        if line.starts_with('\\') {
+            lines.push(RawLine::NoNewline);
+        } else if line.len() >= parents
+            && line.as_bytes()[..parents]
+                .iter()
+                .all(|c| matches!(c, b' ' | b'+' | b'-'))
+        {
+            lines.push(RawLine::Combined(&line[..parents], &line[parents..]));
+        } else {
+            break;
+        }
+        remaining = &remaining[line_end..];
+    }
+    Ok((remaining, lines))
+}
+
+named!(parse_combined_hunk(&str) -> RawHunk<'_>, do_parse!(
+        header: peek!(take_till!(is_new_line)) >>
+        lines_info: parse_combined_lines_info >>
+        lines: call!(parse_combined_lines, lines_info.0.len()) >>
         (RawHunk {
-            line_info,
+            header,
+            line_info: ((lines_info.0)[0].0, (lines_info.0)[0].1, (lines_info.1).0, (lines_info.1).1),
+            heading: lines_info.2,
+            parents: lines_info.0,
             lines
         })
 ));
 
-named!(parse_raw_file(&str) -> RawFile<'_>, do_parse!(
-        header: complete!(parse_raw_file_header) >>
-        hunks: many0!(complete!(parse_raw_file_hunk)) >>
+// "Binary files a/image.png and b/image.png differ\n"
+named!(parse_binary_differ(&str) -> RawBinary<'_>, do_parse!(
+        tag!("Binary files ") >>
+        take_until_and_consume!("\n") >>
+        (RawBinary::Differ)
+));
+
+// "literal 33\n" or "delta 12\n" followed by the encoded data and an empty line
+named!(parse_binary_patch_block(&str) -> (&str, u32), do_parse!(
+        kind: alt!(tag!("literal") | tag!("delta")) >>
+        tag!(" ") >>
+        size: parse_u32 >>
+        tag!("\n") >>
+        many0!(complete!(terminated!(take_till1!(is_new_line), tag!("\n")))) >>
+        opt!(complete!(tag!("\n"))) >>
+        ((kind, size))
+));
+
+named!(parse_binary_patch(&str) -> RawBinary<'_>, do_parse!(
+        tag!("GIT binary patch\n") >>
+        blocks: many0!(complete!(parse_binary_patch_block)) >>
+        (RawBinary::Patch(blocks))
+));
+
+// "*** dir1/list.txt\tMon Mar  4 10:21:31 2019\n"
+named!(parse_context_path_old(&str) -> RawPath<'_>, do_parse!(
+        tag!(FILE_START_CONTEXT_OLD_PATH) >>
+        path: map!(take_until_and_consume!("\n"), get_raw_path) >>
+        (path)
+));
+
+// the header of `diff -c`, the new path has the same line like the old path
+// of a unified diff
+named!(parse_context_file_header(&str) -> RawHeader<'_>, do_parse!(
+        opt!(complete!(parse_diff_command)) >>
+        old: parse_context_path_old >>
+        new: parse_path_old >>
+        (RawHeader {
+            filenames: (old.path.into(), new.path.into()),
+            prefixes: None,
+            extended_headers: Vec::new(),
+            paths: Some((old, new)),
+            git: false
+        })
+));
+
+// "1,5" or "1" for a single line
+named!(parse_context_range(&str) -> (u32, u32), do_parse!(
+        start_line: parse_u32 >>
+        end_line: opt!(preceded!(tag!(","), parse_u32)) >>
+        (start_line, end_line.unwrap_or(start_line))
+));
+
+named!(parse_context_line_both(&str) -> RawContextLine<'_>, do_parse!(
+        tag!("  ") >>
+        content: take_till!(is_new_line) >>
+        (RawContextLine::Both(content))
+));
+
+named!(parse_context_line_changed(&str) -> RawContextLine<'_>, do_parse!(
+        tag!("! ") >>
+        content: take_till!(is_new_line) >>
+        (RawContextLine::Changed(content))
+));
+
+named!(parse_context_line_removed(&str) -> RawContextLine<'_>, do_parse!(
+        tag!("- ") >>
+        content: take_till!(is_new_line) >>
+        (RawContextLine::Single(content))
+));
+
+named!(parse_context_line_added(&str) -> RawContextLine<'_>, do_parse!(
+        tag!("+ ") >>
+        content: take_till!(is_new_line) >>
+        (RawContextLine::Single(content))
+));
+
+named!(parse_context_line_no_newline(&str) -> RawContextLine<'_>, do_parse!(
+        tag!("\\") >>
+        take_till!(is_new_line) >>
+        (RawContextLine::NoNewline)
+));
+
+named!(parse_context_line_old(&str) -> RawContextLine<'_>, do_parse!(
+        line: alt!(parse_context_line_both | parse_context_line_changed |
+                   parse_context_line_removed | parse_context_line_no_newline) >>
+        opt!(alt!(tag!("\n") | eof!())) >>
+        (line)
+));
+
+named!(parse_context_line_new(&str) -> RawContextLine<'_>, do_parse!(
+        line: alt!(parse_context_line_both | parse_context_line_changed |
+                   parse_context_line_added | parse_context_line_no_newline) >>
+        opt!(alt!(tag!("\n") | eof!())) >>
+        (line)
+));
+
+// "***************\n*** 1,5 ****\n  apples\n! pears\n--- 1,5 ----\n  apples\n! plums\n"
+named!(parse_context_hunk(&str) -> RawHunk<'_>, do_parse!(
+        header: peek!(take_till!(is_new_line)) >>
+        tag!("***************") >>
+        heading: take_until_and_consume!("\n") >>
+        tag!("*** ") >>
+        old_range: parse_context_range >>
+        tag!(" ****\n") >>
+        old_lines: many0!(complete!(parse_context_line_old)) >>
+        tag!("--- ") >>
+        new_range: parse_context_range >>
+        tag!(" ----") >>
+        opt!(alt!(tag!("\n") | eof!())) >>
+        new_lines: many0!(complete!(parse_context_line_new)) >>
+        (get_context_hunk(header, old_range.0, old_lines, new_range.0, new_lines, heading.trim()))
+));
+
+named!(parse_context_raw_file(&str) -> RawFile<'_>, do_parse!(
+        header: complete!(parse_context_file_header) >>
+        hunks: many0!(complete!(map_opt!(parse_context_hunk, check_line_numbers))) >>
         (RawFile {
             header,
+            binary: None,
             hunks
         })
 ));
 
-named!(parse_raw_files_intern(&str) -> Vec<RawFile<'_>>,
-       many0!(complete!(parse_raw_file))
-);
+// "2c2", "10a11,12" or "3,4d2"
+named!(parse_normal_command(&str) -> ((u32, u32), char, (u32, u32)), do_parse!(
+        left: parse_context_range >>
+        kind: one_of!("acd") >>
+        right: parse_context_range >>
+        tag!("\n") >>
+        (left, kind, right)
+));
 
-pub fn parse_raw_files<'a>(input: &'a str) -> Result<Vec<RawFile<'a>>, String> {
-    match parse_raw_files_intern(input) {
-        Ok((remaining, result)) => {
-            if !remaining.is_empty() {
-                Err(format!(
-                    "Error parsing file. Remaining is not empty. Remaining: {:?}",
-                    remaining
-                ))
-            } else {
-                Ok(result)
-            }
-        }
-        Err(e) => Err(format!("Error parsing file. {}", e)),
-    }
-}
+named!(parse_normal_line_left(&str) -> RawLine<'_>, do_parse!(
+        tag!("< ") >>
+        content: take_till!(is_new_line) >>
+        (RawLine::Left(content))
+));
 
-pub fn parse_content(input: &String) -> Vec<File> {
-    let raw_files: Vec<RawFile<'_>> = parse_raw_files(input).unwrap();
+named!(parse_normal_line_right(&str) -> RawLine<'_>, do_parse!(
+        tag!("> ") >>
+        content: take_till!(is_new_line) >>
+        (RawLine::Right(content))
+));
 
-    let mut parsed_files: Vec<File> = Vec::new();
+named!(parse_normal_line_old(&str) -> RawLine<'_>, do_parse!(
+        line: alt!(parse_normal_line_left | parse_line_no_newline) >>
+        opt!(alt!(tag!("\n") | eof!())) >>
+        (line)
+));
 
-    for raw_file in raw_files {
-        let filename: String = raw_file.header.filenames.0.into();
-        let mut commit_id: String = "".to_string();
-        let mut modifier: MODIFIER = MODIFIER::MODIFIED;
-        for extended_header in &raw_file.header.extended_headers {
-            match extended_header {
-                ExtendedHeader::Index(index) => commit_id = index.to_string(),
-                ExtendedHeader::NewFile => modifier = MODIFIER::ADD,
-                ExtendedHeader::Deleted => modifier = MODIFIER::DELETE,
-                ExtendedHeader::RenameFile(_) => modifier = MODIFIER::RENAMED,
-                _ => modifier = MODIFIER::MODIFIED,
-            }
-        }
+named!(parse_normal_line_new(&str) -> RawLine<'_>, do_parse!(
+        line: alt!(parse_normal_line_right | parse_line_no_newline) >>
+        opt!(alt!(tag!("\n") | eof!())) >>
+        (line)
+));
 
-        let mut hunks: Vec<Hunk> = Vec::new();
-        for hunk in &raw_file.hunks {
-            let mut lines: Vec<LINE> = Vec::new();
-            let mut line_nr_left = hunk.line_info.0;
-            let mut line_nr_right = hunk.line_info.2;
+// "2c2\n< pears\n---\n> plums\n"
+named!(parse_normal_hunk(&str) -> RawHunk<'_>, do_parse!(
+        header: peek!(take_till!(is_new_line)) >>
+        command: parse_normal_command >>
+        old_lines: many0!(complete!(parse_normal_line_old)) >>
+        opt!(complete!(tag!("---\n"))) >>
+        new_lines: many0!(complete!(parse_normal_line_new)) >>
+        (get_normal_hunk(header, command, old_lines, new_lines))
+));
 
-            for line in &hunk.lines {
-                match line {
-                    RawLine::Left(content) => {
-                        lines.push(LINE::REM {
-                            number: line_nr_left as usize,
-                            line: String::from(*content),
-                        });
-                        line_nr_left += 1;
-                    }
-                    RawLine::Right(content) => {
-                        lines.push(LINE::ADD {
-                            number: line_nr_right as usize,
-                            line: String::from(*content),
-                        });
-                        line_nr_right += 1;
-                    }
-                    RawLine::Both(content) => {
-                        lines.push(LINE::NOP {
-                            number_left: line_nr_left as usize,
-                            number_right: line_nr_right as usize,
-                            line: String::from(*content),
-                        });
-                        line_nr_right += 1;
-                        line_nr_left += 1;
-                    }
+named!(parse_normal_raw_file(&str) -> RawFile<'_>, do_parse!(
+        command: opt!(complete!(parse_diff_command)) >>
+        hunks: many1!(complete!(map_opt!(parse_normal_hunk, check_line_numbers))) >>
+        (RawFile {
+            header: get_normal_header(command),
+            binary: None,
+            hunks
+        })
+));
+
+named!(parse_unified_raw_file(&str) -> RawFile<'_>, do_parse!(
+        header: alt!(complete!(parse_raw_file_header) | complete!(parse_unified_file_header)) >>
+        binary: opt!(complete!(alt!(parse_binary_differ | parse_binary_patch))) >>
+        // the `---` and `+++` lines of a unified diff start the next file
+        hunks: many0!(complete!(preceded!(
+            not!(parse_git_paths),
+            map_opt!(alt!(parse_raw_file_hunk | parse_combined_hunk), check_line_numbers)
+        ))) >>
+        (RawFile {
+            header,
+            binary,
+            hunks
+        })
+));
+
+/// Returns the hunk if the numbers of all its lines are valid line numbers,
+/// otherwise `None`, so the hunk isn't parsed like a hunk header with an
+/// invalid number. The number after the last line is counted as well.
+fn check_line_numbers(hunk: RawHunk<'_>) -> Option<RawHunk<'_>> {
+    let mut line_nr_left = hunk.line_info.0;
+    let mut line_nr_right = hunk.line_info.2;
+    let mut line_nrs_parents: Vec<u32> = hunk.parents.iter().map(|range| range.0).collect();
+
+    for line in &hunk.lines {
+        match line {
+            RawLine::Left(_) => line_nr_left = line_nr_left.checked_add(1)?,
+            RawLine::Right(_) => line_nr_right = line_nr_right.checked_add(1)?,
+            RawLine::Both(_) => {
+                line_nr_left = line_nr_left.checked_add(1)?;
+                line_nr_right = line_nr_right.checked_add(1)?;
+            }
+            // at most one more line in every parent and the result
+            RawLine::Combined(..) => {
+                for line_nr in line_nrs_parents.iter_mut() {
+                    *line_nr = line_nr.checked_add(1)?;
                 }
+                line_nr_right = line_nr_right.checked_add(1)?;
             }
-            hunks.push(Hunk::new(lines));
+            RawLine::NoNewline => {}
         }
-        parsed_files.push(File::new(modifier, filename, commit_id, hunks))
     }
-    parsed_files
+    Some(hunk)
 }
 
-/* --------------------------------------------------------- */
-/* ------------------------- TESTS ------------------------- */
-/* --------------------------------------------------------- */
-#[cfg(test)]
-mod tests {
-    use super::*;
+named!(parse_raw_file(&str) -> RawFile<'_>,
+       alt!(parse_unified_raw_file | parse_context_raw_file | parse_normal_raw_file)
+);
 
-    #[test]
-    fn parse_filename_test() {
-        let input = "diff --git a/script.sh b/script.sh\n";
-        match parse_filename(&input[..]) {
-            Ok((remaining, result)) => {
-                assert!(remaining.is_empty());
-                assert_eq!("script.sh", result.0);
-            }
-            Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
-            }
-        }
+fn get_binary_size(block: &(&str, u32)) -> BINARY_SIZE {
+    match block {
+        ("delta", size) => BINARY_SIZE::DELTA(*size as usize),
+        (_, size) => BINARY_SIZE::LITERAL(*size as usize),
     }
+}
 
-    #[test]
-    fn parse_extended_header_mode_test() {
-        let input = "old mode 100644\nnew mode 100755\n";
-        match parse_extended_header_mode(&input[..]) {
-            Ok((_remaining, result)) => {
-                assert_eq!(ExtendedHeader::ChMode(("100644", "100755")), result);
+/// The settings of the parsers. New options can be added in a minor version,
+/// so start with the defaults and change the fields.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// the prefixes of the old and the new names of `diff --git`, e.g. of
+    /// `--src-prefix` and `--dst-prefix` or two empty prefixes of
+    /// `--no-prefix`. If they are `None` or the names don't start with them,
+    /// the prefixes are guessed from the names.
+    pub prefixes: Option<(String, String)>,
+}
+
+/// Returns the files of the input, also the files of the commits, parts
+/// which are no diff are skipped
+///
+/// # Arguments
+///
+/// * `input` - the diff output
+///
+pub fn parse_content(input: &str) -> Vec<File> {
+    parse_sections(input)
+        .into_iter()
+        .flat_map(|section| match section {
+            SECTION::FILE(file) => vec![file],
+            SECTION::COMMIT(commit) => commit.files,
+            SECTION::TEXT(_) => Vec::new(),
+        })
+        .collect()
+}
+
+/// Returns the input split into the parsed files, the commits and the text
+/// between them. A file section which can't be parsed is kept as text up to
+/// the start of the next file, so nothing of the input is lost.
+///
+/// # Arguments
+///
+/// * `input` - the diff output
+///
+pub fn parse_sections(input: &str) -> Vec<SECTION> {
+    parse_sections_with_options(input, &ParseOptions::default())
+}
+
+/// Returns the sections of the input like `parse_sections`, the filenames
+/// are read with the options
+///
+/// # Arguments
+///
+/// * `input` - the diff output
+/// * `options` - the prefixes of the filenames
+///
+pub fn parse_sections_with_options(input: &str, options: &ParseOptions) -> Vec<SECTION> {
+    let mut sections: Vec<SECTION> = Vec::new();
+    let mut remaining = input;
+
+    while !remaining.is_empty() {
+        let after_commit = matches!(sections.last(), Some(SECTION::COMMIT(_)));
+        let (rest, raw_section) = parse_section(remaining, after_commit);
+        match raw_section {
+            RawSection::Commit(raw_commit) => {
+                sections.push(SECTION::COMMIT(get_commit(&raw_commit)))
             }
-            Err(e) => {
-                println!("Error {:?}", e);
+            // the files directly after a commit are part of it
+            RawSection::File(raw_file) => {
+                let file = get_file(&raw_file, options);
+                match sections.last_mut() {
+                    Some(SECTION::COMMIT(commit)) => commit.files.push(file),
+                    _ => sections.push(SECTION::FILE(file)),
+                }
             }
+            RawSection::Signature => {}
+            RawSection::Text(text) => match sections.last_mut() {
+                Some(SECTION::TEXT(last_text)) => last_text.push_str(text),
+                _ => sections.push(SECTION::TEXT(text.to_string())),
+            },
         }
+        remaining = rest;
     }
+    sections
+}
 
-    #[test]
-    fn parse_extended_header_deleted_test() {
-        let input = "deleted file mode 100644\n";
-        match parse_extended_header_deleted(&input[..]) {
-            Ok((_remaining, result)) => {
-                assert_eq!(ExtendedHeader::Deleted, result);
-            }
-            Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
-            }
+/// Returns the first section of the input and the rest after it. A part
+/// which can't be parsed is text up to the start of the next file or commit,
+/// but at least one line.
+///
+/// # Arguments
+///
+/// * `input` - the diff output
+/// * `after_commit` - if the section before is a commit, only then the
+///   signature of a mail is skipped
+///
+fn parse_section(input: &str, after_commit: bool) -> (&str, RawSection<'_>) {
+    if is_commit_start(input) {
+        if let Ok((rest, raw_commit)) = parse_commit(input) {
+            return (rest, RawSection::Commit(raw_commit));
         }
     }
-
-    #[test]
-    fn parse_extended_header_new_file_test() {
-        let input = "new file mode 100644\n";
-        match parse_extended_header_new_file(&input[..]) {
-            Ok((_remaining, result)) => {
-                assert_eq!(ExtendedHeader::NewFile, result);
-            }
-            Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
-            }
+    if is_file_start(input) {
+        if let Ok((rest, raw_file)) = parse_raw_file(input) {
+            return (rest, RawSection::File(raw_file));
         }
     }
-
-    #[test]
-    fn parse_extended_header_copy_file_test() {
-        let input = "copy from path/to/file/a\ncopy to path/to/file/b\n";
-        match parse_extended_header_copy_file(&input[..]) {
-            Ok((_remaining, result)) => {
-                assert_eq!(
-                    ExtendedHeader::CopyFile(("path/to/file/a", "path/to/file/b")),
-                    result
-                );
-            }
-            Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
-            }
+    if after_commit {
+        if let Ok((rest, _)) = parse_patch_signature(input) {
+            return (rest, RawSection::Signature);
         }
     }
 
-    #[test]
-    fn parse_extended_header_rename_file_test() {
-        let input = "rename from path/to/file/a\nrename to path/to/file/b\n";
-        match parse_extended_header_rename_file(&input[..]) {
-            Ok((_remaining, result)) => {
-                assert_eq!(
-                    ExtendedHeader::RenameFile(("path/to/file/a", "path/to/file/b")),
-                    result
-                );
+    let line_end = input.find('\n').map_or(input.len(), |i| i + 1);
+    let text_end = line_end + find_section_start(&input[line_end..]);
+    (&input[text_end..], RawSection::Text(&input[..text_end]))
+}
+
+/// Parses the input line by line and returns every section as soon as it is
+/// complete, so a long diff can be shown while it is read. A section is
+/// complete if the start of the next file or commit follows it. The files of
+/// a commit are returned as own sections after the commit. Text outside of a
+/// file or a commit is returned line by line, when no line before could
+/// still start a section. A normal diff without `diff` command lines has no
+/// start after it, it is returned at the end of the input.
+pub struct StreamParser {
+    /// the lines which aren't returned as sections yet
+    buffer: String,
+    after_commit: bool,
+    /// the offsets in the buffer of the lines which can start a file or a
+    /// commit and the count of the next lines to check them
+    starts: Vec<(usize, usize)>,
+    /// the count of the old and the new lines left in the current hunk of a
+    /// unified diff
+    hunk_lines: (u32, u32),
+    options: ParseOptions,
+}
+
+impl Default for StreamParser {
+    fn default() -> StreamParser {
+        StreamParser::new()
+    }
+}
+
+impl StreamParser {
+    pub fn new() -> StreamParser {
+        StreamParser::with_options(ParseOptions::default())
+    }
+
+    /// Returns a parser which reads the filenames with the options
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - the prefixes of the filenames
+    ///
+    pub fn with_options(options: ParseOptions) -> StreamParser {
+        StreamParser {
+            buffer: String::new(),
+            after_commit: false,
+            starts: Vec::new(),
+            hunk_lines: (0, 0),
+            options,
+        }
+    }
+
+    /// Adds a line of the input and returns the sections which are complete
+    /// with it. Only a file or commit start can complete a section, so the
+    /// buffer is parsed only if a line with its prefix is one of them. The
+    /// lines of a hunk are skipped, e.g. a removed `-- ` line is no `---`
+    /// line of the next file. Text is returned at once if it can't be the
+    /// start of a section.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - a line of the input with its newline
+    ///
+    pub fn push_line(&mut self, line: &str) -> Vec<SECTION> {
+        let offset = self.buffer.len();
+        self.buffer.push_str(line);
+        self.starts.retain_mut(|(_, lines)| {
+            *lines -= 1;
+            *lines > 0
+        });
+        if self.push_hunk_line(line) {
+            return Vec::new();
+        }
+        // the command of a normal hunk like "2c2" is followed by its lines
+        if SECTION_START_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+            || parse_normal_command(line).is_ok()
+        {
+            self.starts.push((offset, SECTION_START_LINES));
+        }
+
+        let is_start = self.starts.iter().any(|(offset, _)| {
+            let rest = &self.buffer[*offset..];
+            is_file_start(rest) || is_commit_start(rest)
+        });
+        if is_start {
+            return self.take_sections(false);
+        }
+        if self.starts.is_empty() {
+            return self.take_text();
+        }
+        Vec::new()
+    }
+
+    /// Returns the text at the start of the buffer up to the first line
+    /// which may start a file or a commit and removes it from the buffer.
+    /// The empty lines at the end are kept, they are part of a commit which
+    /// may follow.
+    fn take_text(&mut self) -> Vec<SECTION> {
+        let mut length = 0;
+        for line in self.buffer.split_inclusive('\n') {
+            let rest = &self.buffer[length..];
+            if rest.trim_start_matches('\n').is_empty()
+                || is_file_start(rest)
+                || is_commit_start(rest)
+                || parse_commit_header_start(rest).is_ok()
+            {
+                break;
             }
-            Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+            length += line.len();
+        }
+        if length == 0 {
+            return Vec::new();
+        }
+        let text: String = self.buffer.drain(..length).collect();
+        self.after_commit = false;
+        vec![SECTION::TEXT(text)]
+    }
+
+    /// Counts a line of the current hunk and returns `true` for it, a hunk
+    /// header starts to count the lines of a new hunk. Like for the parser a
+    /// hunk ends after its line counts or at a line which is no hunk line.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - a line of the input with its newline
+    ///
+    fn push_hunk_line(&mut self, line: &str) -> bool {
+        let (left, right) = &mut self.hunk_lines;
+        let in_hunk = (*left > 0 || *right > 0)
+            && match line.as_bytes().first() {
+                Some(b' ') => {
+                    *left = left.saturating_sub(1);
+                    *right = right.saturating_sub(1);
+                    true
+                }
+                Some(b'-') => {
+                    *left = left.saturating_sub(1);
+                    true
+                }
+                Some(b'+') => {
+                    *right = right.saturating_sub(1);
+                    true
+                }
+                Some(b'\\') => true,
+                _ => false,
+            };
+        if !in_hunk {
+            self.hunk_lines = match parse_lines_info(line) {
+                Ok((_, (line_info, _))) => (line_info.1, line_info.3),
+                Err(_) => (0, 0),
+            };
+        }
+        in_hunk
+    }
+
+    /// Returns the sections of the rest of the input at its end
+    pub fn finish(mut self) -> Vec<SECTION> {
+        self.take_sections(true)
+    }
+
+    /// Returns the complete sections at the start of the buffer and removes
+    /// them from it
+    fn take_sections(&mut self, end: bool) -> Vec<SECTION> {
+        let mut raw_sections: Vec<RawSection<'_>> = Vec::new();
+        let mut complete = (0, 0, self.after_commit);
+        let mut after_commit = self.after_commit;
+        let mut remaining = self.buffer.as_str();
+
+        while !remaining.is_empty() {
+            let (rest, raw_section) = parse_section(remaining, after_commit);
+            after_commit = match raw_section {
+                RawSection::Commit(_) => true,
+                RawSection::Text(_) => false,
+                _ => after_commit,
+            };
+            raw_sections.push(raw_section);
+            remaining = rest;
+            if end || is_file_start(remaining) || is_commit_start(remaining) {
+                complete = (
+                    raw_sections.len(),
+                    self.buffer.len() - remaining.len(),
+                    after_commit,
+                );
             }
         }
+
+        let (count, length, after_commit) = complete;
+        let sections: Vec<SECTION> = raw_sections
+            .iter()
+            .take(count)
+            .filter_map(|raw_section| match raw_section {
+                RawSection::Commit(raw_commit) => Some(SECTION::COMMIT(get_commit(raw_commit))),
+                RawSection::File(raw_file) => {
+                    Some(SECTION::FILE(get_file(raw_file, &self.options)))
+                }
+                RawSection::Signature => None,
+                RawSection::Text(text) => Some(SECTION::TEXT(text.to_string())),
+            })
+            .collect();
+        self.buffer.drain(..length);
+        self.starts.retain(|(offset, _)| *offset >= length);
+        for (offset, _) in self.starts.iter_mut() {
+            *offset -= length;
+        }
+        self.after_commit = after_commit;
+        sections
     }
+}
 
-    #[test]
-    fn parse_extended_header_similarity_index_test() {
-        let input = "similarity index 80%\n";
-        match parse_extended_header_similarity_index(&input[..]) {
-            Ok((_remaining, result)) => {
-                assert_eq!(ExtendedHeader::SimilarityIndex("80%"), result);
+/// Returns the files of the input or the first part which is no valid diff.
+/// Besides the syntax the line counts of the hunks are checked. The commit
+/// headers of `git log -p` and the mail headers and signatures of
+/// `git format-patch` are valid, but skipped.
+///
+/// # Arguments
+///
+/// * `input` - the diff output
+///
+pub fn parse_strict(input: &str) -> Result<Vec<File>, ParseError> {
+    parse_strict_with_options(input, &ParseOptions::default())
+}
+
+/// Returns the files of the input like `parse_strict`, the filenames are
+/// read with the options
+///
+/// # Arguments
+///
+/// * `input` - the diff output
+/// * `options` - the prefixes of the filenames
+///
+pub fn parse_strict_with_options(
+    input: &str,
+    options: &ParseOptions,
+) -> Result<Vec<File>, ParseError> {
+    let mut files: Vec<File> = Vec::new();
+    let mut remaining = input;
+    let mut after_commit = false;
+
+    while !remaining.is_empty() {
+        if is_commit_start(remaining) {
+            if let Ok((rest, _)) = parse_commit(remaining) {
+                after_commit = true;
+                remaining = rest;
+                continue;
+            }
+        }
+        if after_commit {
+            if let Ok((rest, _)) = parse_patch_signature(remaining) {
+                remaining = rest;
+                continue;
+            }
+        }
+
+        // the rest of the input is the end of the input
+        let offset = input.len() - remaining.len();
+        let (line, column) = get_position(input, offset);
+        if !is_file_start(remaining) {
+            return Err(match files.last() {
+                None => ParseError::NoDiff { line },
+                Some(file) => ParseError::Unexpected {
+                    line,
+                    file: file.filename.clone(),
+                    expected: if file.binary.is_some() {
+                        "file header"
+                    } else if file.hunks.is_empty() {
+                        "extended header or hunk header"
+                    } else {
+                        "hunk line or hunk header"
+                    },
+                },
+            });
+        }
+
+        match parse_raw_file(remaining) {
+            Ok((rest, raw_file)) => {
+                let file = get_file(&raw_file, options);
+                check_line_counts(input, offset, &raw_file, &file.filename)?;
+                files.push(file);
+                remaining = rest;
             }
             Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+                // the position where the header parser has failed
+                let (line, column) = match e {
+                    nom::Err::Error(nom::Context::Code(rest, _))
+                    | nom::Err::Failure(nom::Context::Code(rest, _)) => {
+                        get_position(input, input.len().saturating_sub(rest.len()))
+                    }
+                    _ => (line, column),
+                };
+                return Err(ParseError::Header { line, column });
             }
         }
     }
+    Ok(files)
+}
 
-    #[test]
-    fn parse_extended_header_dissimilarity_index_test() {
-        let input = "dissimilarity index 20%\n";
-        match parse_extended_header_dissimilarity_index(&input[..]) {
-            Ok((_remaining, result)) => {
-                assert_eq!(ExtendedHeader::DissimilarityIndex("20%"), result);
+/// Checks the count of the removed and added lines of every hunk against the
+/// counts in the hunk header
+///
+/// # Arguments
+///
+/// * `input` - the whole input
+/// * `offset` - the byte offset of the file in the input
+/// * `raw_file` - the parsed file
+/// * `filename` - the filename for the error
+///
+fn check_line_counts(
+    input: &str,
+    offset: usize,
+    raw_file: &RawFile<'_>,
+    filename: &str,
+) -> Result<(), ParseError> {
+    for hunk in &raw_file.hunks {
+        let mut found = (0, 0);
+        for line in &hunk.lines {
+            match line {
+                RawLine::Left(_) => found.0 += 1,
+                RawLine::Right(_) => found.1 += 1,
+                RawLine::Both(_) => {
+                    found.0 += 1;
+                    found.1 += 1;
+                }
+                // the line info has the first parent and the result
+                RawLine::Combined(changes, _) => {
+                    let in_result = !changes.contains('-');
+                    if changes.starts_with('-') || (in_result && changes.starts_with(' ')) {
+                        found.0 += 1;
+                    }
+                    if in_result {
+                        found.1 += 1;
+                    }
+                }
+                RawLine::NoNewline => {}
             }
-            Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+        }
+        let expected = (hunk.line_info.1 as usize, hunk.line_info.3 as usize);
+        if found != expected {
+            return Err(ParseError::LineCount {
+                line: get_position(input, get_offset(input, hunk.header).unwrap_or(offset)).0,
+                file: filename.to_string(),
+                expected,
+                found,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the line and column of a position in the input
+///
+/// # Arguments
+///
+/// * `input` - the whole input
+/// * `offset` - the byte offset of the position, the whole input if it is
+///   outside of it
+///
+fn get_position(input: &str, offset: usize) -> (usize, usize) {
+    let before = input.get(..offset).unwrap_or(input);
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Returns the byte offset of a part of the input or `None` if the part is
+/// no slice of the input
+///
+/// # Arguments
+///
+/// * `input` - the whole input
+/// * `part` - a slice of the input
+///
+fn get_offset(input: &str, part: &str) -> Option<usize> {
+    let offset = (part.as_ptr() as usize).checked_sub(input.as_ptr() as usize)?;
+    if offset.checked_add(part.len())? <= input.len() {
+        Some(offset)
+    } else {
+        None
+    }
+}
+
+/// Returns the position of the next line with the start of a file or a
+/// commit section or the length of the input
+fn find_section_start(input: &str) -> usize {
+    let mut position = 0;
+    for line in input.split_inclusive('\n') {
+        if is_file_start(&input[position..]) || is_commit_start(&input[position..]) {
+            break;
+        }
+        position += line.len();
+    }
+    position
+}
+
+/// Checks if the input starts with a diff command like `diff --git`, the
+/// `---` and `+++` lines of a unified diff or the `***` and `---` lines of a
+/// context diff
+fn is_file_start(input: &str) -> bool {
+    let (first_line, next_line) = match input.find('\n') {
+        Some(i) => (&input[..i], &input[i + 1..]),
+        None => (input, ""),
+    };
+    first_line.starts_with(FILE_START_COMMAND)
+        || is_normal_hunk_start(input)
+        || (first_line.starts_with(FILE_START_OLD_PATH)
+            && next_line.starts_with(FILE_START_NEW_PATH))
+        || (first_line.starts_with(FILE_START_CONTEXT_OLD_PATH)
+            && !first_line.ends_with(CONTEXT_RANGE_OLD_END)
+            && next_line.starts_with(FILE_START_OLD_PATH))
+}
+
+/// Checks if the input starts with a commit header of `git log -p` or the
+/// mail headers of `git format-patch`, empty lines before it are part of the
+/// commit
+fn is_commit_start(input: &str) -> bool {
+    let first_line = input.trim_start_matches('\n');
+    (first_line.starts_with(COMMIT_START) || first_line.starts_with(PATCH_START))
+        && parse_commit(input).is_ok()
+}
+
+/// Checks if the input starts with the signature of a mail
+fn is_patch_signature(input: &str) -> bool {
+    input.starts_with(PATCH_SIGNATURE) && parse_patch_signature(input).is_ok()
+}
+
+/// Checks if the input starts with the command of a normal diff hunk like
+/// `2c2`, followed by an old or a new line
+fn is_normal_hunk_start(input: &str) -> bool {
+    match parse_normal_command(input) {
+        Ok((rest, _)) => rest.starts_with("< ") || rest.starts_with("> "),
+        Err(_) => false,
+    }
+}
+
+/// Returns the header of a normal diff. Only the diff command of `diff -r`
+/// has the file names, the last two arguments.
+fn get_normal_header(command: Option<&str>) -> RawHeader<'_> {
+    let filenames = command.and_then(|command| {
+        let mut arguments = command.rsplitn(3, ' ');
+        let new = arguments.next()?;
+        let old = arguments.next()?;
+        Some((old, new))
+    });
+    match filenames {
+        Some((old, new)) => RawHeader {
+            filenames: (old.into(), new.into()),
+            prefixes: None,
+            extended_headers: Vec::new(),
+            paths: Some((
+                RawPath {
+                    path: old,
+                    timestamp: None,
+                },
+                RawPath {
+                    path: new,
+                    timestamp: None,
+                },
+            )),
+            git: false,
+        },
+        None => RawHeader {
+            filenames: ("".into(), "".into()),
+            prefixes: None,
+            extended_headers: Vec::new(),
+            paths: None,
+            git: false,
+        },
+    }
+}
+
+/// Converts a hunk of a normal diff to a unified hunk. The line range of the
+/// side without lines of an add (`a`) or delete (`d`) command is the line
+/// before the change, like in a unified diff.
+///
+/// # Arguments
+///
+/// * `header` - the command line of the hunk in the input
+/// * `command` - the old line range, the kind of the change and the new line range
+/// * `old_lines` - the removed lines
+/// * `new_lines` - the added lines
+///
+fn get_normal_hunk<'a>(
+    header: &'a str,
+    command: ((u32, u32), char, (u32, u32)),
+    old_lines: Vec<RawLine<'a>>,
+    new_lines: Vec<RawLine<'a>>,
+) -> RawHunk<'a> {
+    let (left, kind, right) = command;
+    let count = |range: (u32, u32)| range.1.saturating_add(1).saturating_sub(range.0);
+    let left_count = if kind == 'a' { 0 } else { count(left) };
+    let right_count = if kind == 'd' { 0 } else { count(right) };
+    let mut lines = old_lines;
+    lines.extend(new_lines);
+    RawHunk {
+        header,
+        line_info: (left.0, left_count, right.0, right_count),
+        heading: "",
+        parents: Vec::new(),
+        lines,
+    }
+}
+
+/// Converts the old and the new lines of a context hunk to the lines of a
+/// unified hunk. The lines of a side without changes are left out in the
+/// context format, they are the unchanged lines of the other side. A changed
+/// block is converted to its old lines followed by its new lines.
+///
+/// # Arguments
+///
+/// * `header` - the separator line of the hunk in the input
+/// * `old_start` - the first line of the old lines
+/// * `old_lines` - the lines after the old line range
+/// * `new_start` - the first line of the new lines
+/// * `new_lines` - the lines after the new line range
+/// * `heading` - the section heading after the hunk separator
+///
+fn get_context_hunk<'a>(
+    header: &'a str,
+    old_start: u32,
+    old_lines: Vec<RawContextLine<'a>>,
+    new_start: u32,
+    new_lines: Vec<RawContextLine<'a>>,
+    heading: &'a str,
+) -> RawHunk<'a> {
+    let unchanged = |lines: &[RawContextLine<'a>]| -> Vec<RawContextLine<'a>> {
+        lines
+            .iter()
+            .filter_map(|line| match line {
+                RawContextLine::Both(content) => Some(RawContextLine::Both(content)),
+                _ => None,
+            })
+            .collect()
+    };
+    let old_lines = if old_lines.is_empty() {
+        unchanged(&new_lines)
+    } else {
+        old_lines
+    };
+    let new_lines = if new_lines.is_empty() {
+        unchanged(&old_lines)
+    } else {
+        new_lines
+    };
+
+    let mut lines: Vec<RawLine<'a>> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    // a no newline marker belongs to the line before
+    let mut push_line =
+        |lines_side: &[RawContextLine<'a>], index: &mut usize, line: RawLine<'a>| {
+            lines.push(line);
+            *index += 1;
+            if let Some(RawContextLine::NoNewline) = lines_side.get(*index) {
+                lines.push(RawLine::NoNewline);
+                *index += 1;
+            }
+        };
+    loop {
+        match (old_lines.get(i), new_lines.get(j)) {
+            (Some(RawContextLine::NoNewline), _) => i += 1,
+            (_, Some(RawContextLine::NoNewline)) => j += 1,
+            (Some(RawContextLine::Single(content)), _) => {
+                push_line(&old_lines, &mut i, RawLine::Left(content))
+            }
+            (_, Some(RawContextLine::Single(content))) => {
+                push_line(&new_lines, &mut j, RawLine::Right(content))
             }
+            (Some(RawContextLine::Changed(_)), _) | (_, Some(RawContextLine::Changed(_))) => {
+                while let Some(RawContextLine::Changed(content)) = old_lines.get(i) {
+                    push_line(&old_lines, &mut i, RawLine::Left(content));
+                }
+                while let Some(RawContextLine::Changed(content)) = new_lines.get(j) {
+                    push_line(&new_lines, &mut j, RawLine::Right(content));
+                }
+            }
+            (Some(RawContextLine::Both(content)), Some(RawContextLine::Both(_))) => {
+                push_line(&old_lines, &mut i, RawLine::Both(content));
+                j += 1;
+            }
+            (Some(RawContextLine::Both(content)), None) => {
+                push_line(&old_lines, &mut i, RawLine::Both(content))
+            }
+            (None, Some(RawContextLine::Both(content))) => {
+                push_line(&new_lines, &mut j, RawLine::Both(content))
+            }
+            (None, None) => break,
         }
     }
 
-    #[test]
-    fn parse_extended_header_index_test() {
-        let input = "index 089fe5f..384ac88 100644\n@@";
-        match parse_extended_header_index(&input[..]) {
-            Ok((remaining, result)) => {
-                assert_eq!("@@", remaining);
-                assert_eq!(ExtendedHeader::Index("384ac88"), result);
+    let old_count = lines
+        .iter()
+        .filter(|line| matches!(line, RawLine::Left(_) | RawLine::Both(_)))
+        .count() as u32;
+    let new_count = lines
+        .iter()
+        .filter(|line| matches!(line, RawLine::Right(_) | RawLine::Both(_)))
+        .count() as u32;
+    RawHunk {
+        header,
+        line_info: (old_start, old_count, new_start, new_count),
+        heading,
+        parents: Vec::new(),
+        lines,
+    }
+}
+
+/// Returns the old and the new filename of a `diff --git` line with the
+/// prefixes. Quoted names are unquoted. The unquoted names are split where
+/// they match the `---` and `+++` paths or the rename and copy headers, else
+/// in the middle if they are equal after the prefixes, so a name may contain
+/// spaces or " b/". The names are cross-checked with the paths, `None` is
+/// returned if they differ.
+///
+/// # Arguments
+///
+/// * `names` - the names after `diff --git `
+/// * `paths` - the paths of the `---` and `+++` lines
+/// * `extended_headers` - the extended headers with the rename and copy
+///   headers
+///
+fn get_git_filenames<'a>(
+    names: &'a str,
+    paths: Option<&(RawPath<'a>, RawPath<'a>)>,
+    extended_headers: &[ExtendedHeader<'a>],
+) -> Option<(Cow<'a, str>, Cow<'a, str>)> {
+    let (old, new) = split_git_filenames(names, paths, extended_headers)?;
+    let (old, new) = (unquote_path(old), unquote_path(new));
+
+    if let Some((old_path, new_path)) = paths {
+        let differs = |path: &RawPath<'_>, name: &str| {
+            path.path != DEV_NULL && unquote_path(path.path) != name
+        };
+        if differs(old_path, &old) || differs(new_path, &new) {
+            return None;
+        }
+    }
+    Some((old, new))
+}
+
+/// Returns the old and the new name of a `diff --git` line with their quotes
+/// and prefixes
+///
+/// # Arguments
+///
+/// * `names` - the names after `diff --git `
+/// * `paths` - the paths of the `---` and `+++` lines
+/// * `extended_headers` - the extended headers with the rename and copy
+///   headers
+///
+fn split_git_filenames<'a>(
+    names: &'a str,
+    paths: Option<&(RawPath<'a>, RawPath<'a>)>,
+    extended_headers: &[ExtendedHeader<'a>],
+) -> Option<(&'a str, &'a str)> {
+    if names.starts_with('"') {
+        let end = get_quoted_end(names)?;
+        return Some((&names[..end], names[end..].strip_prefix(' ')?));
+    }
+    // an unquoted name has no quotes
+    if let Some(i) = names.find(" \"") {
+        return Some((&names[..i], &names[i + 1..]));
+    }
+
+    let splits: Vec<(&str, &str)> = names
+        .match_indices(' ')
+        .map(|(i, _)| (&names[..i], &names[i + 1..]))
+        .collect();
+    let is_path = |path: &RawPath<'_>, name: &str| path.path != DEV_NULL && path.path == name;
+    let renamed = extended_headers.iter().find_map(|header| match header {
+        ExtendedHeader::RenameFile(names) | ExtendedHeader::CopyFile(names) => Some(*names),
+        _ => None,
+    });
+    let is_equal = |old: &str, new: &str| {
+        old == new
+            || matches!(
+                (old.split_once('/'), new.split_once('/')),
+                (Some((_, old)), Some((_, new))) if old == new
+            )
+    };
+
+    splits
+        .iter()
+        .find(|(old, new)| {
+            paths.is_some_and(|(old_path, new_path)| {
+                is_path(old_path, old) || is_path(new_path, new)
+            })
+        })
+        .or_else(|| {
+            splits.iter().find(|(old, new)| {
+                renamed.is_some_and(|(from, to)| old.ends_with(from) && new.ends_with(to))
+            })
+        })
+        .or_else(|| splits.iter().find(|(old, new)| is_equal(old, new)))
+        .or_else(|| splits.iter().find(|(_, new)| new.starts_with("b/")))
+        .copied()
+}
+
+/// Returns the lengths of the prefixes of the old and the new name, the parts
+/// before the longest equal end of both names which starts a path component,
+/// so the prefixes of `--src-prefix`, `--dst-prefix` and `--no-prefix` are
+/// found. Names without an equal end, e.g. of a rename, lose only the `a/`
+/// and `b/` prefixes.
+fn get_git_prefixes(old: &str, new: &str) -> (usize, usize) {
+    let equal_end: usize = old
+        .chars()
+        .rev()
+        .zip(new.chars().rev())
+        .take_while(|(old_char, new_char)| old_char == new_char)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+    let is_component_start = |name: &str, start: usize| start == 0 || name[..start].ends_with('/');
+
+    let suffix = &old[old.len() - equal_end..];
+    let start = suffix.char_indices().map(|(i, _)| i).find(|i| {
+        is_component_start(old, old.len() - equal_end + i)
+            && is_component_start(new, new.len() - equal_end + i)
+    });
+    match start {
+        Some(i) => (old.len() - equal_end + i, new.len() - equal_end + i),
+        None => (
+            if old.starts_with("a/") { 2 } else { 0 },
+            if new.starts_with("b/") { 2 } else { 0 },
+        ),
+    }
+}
+
+/// Returns the position after the closing quote of a quoted name at the start
+/// of the input
+fn get_quoted_end(input: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in input.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns a path quoted by git without the quotes and with the escaped chars,
+/// the octal escapes are the bytes of non-ASCII chars. Other paths are
+/// returned unchanged.
+///
+/// # Arguments
+///
+/// * `path` - the path, e.g. `"my file\303\251.txt"`
+///
+fn unquote_path(path: &str) -> Cow<'_, str> {
+    let quoted = match path
+        .strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+    {
+        Some(quoted) if path.len() > 1 => quoted,
+        _ => return Cow::Borrowed(path),
+    };
+    let mut bytes: Vec<u8> = Vec::with_capacity(quoted.len());
+    let mut chars = quoted.bytes().peekable();
+    while let Some(byte) = chars.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        match chars.next() {
+            Some(b'a') => bytes.push(0x07),
+            Some(b'b') => bytes.push(0x08),
+            Some(b't') => bytes.push(b'\t'),
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b'v') => bytes.push(0x0b),
+            Some(b'f') => bytes.push(0x0c),
+            Some(b'r') => bytes.push(b'\r'),
+            Some(digit @ b'0'..=b'7') => {
+                let mut value = u32::from(digit - b'0');
+                for _ in 0..2 {
+                    match chars.peek() {
+                        Some(digit @ b'0'..=b'7') => {
+                            value = value * 8 + u32::from(digit - b'0');
+                            chars.next();
+                        }
+                        _ => break,
+                    }
+                }
+                bytes.push(value as u8);
             }
-            Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+            Some(other) => bytes.push(other),
+            None => bytes.push(b'\\'),
+        }
+    }
+    Cow::Owned(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Returns the filename and the old filename of a unified diff. Equal paths
+/// after the first directory, like `a/` and `b/` or the compared
+/// directories of `diff -r`, are shortened to the path in that directory.
+///
+/// # Arguments
+///
+/// * `old` - the path of the `---` line
+/// * `new` - the path of the `+++` line
+///
+fn get_unified_filenames(old: &str, new: &str) -> (String, Option<String>) {
+    if old == DEV_NULL || old == new {
+        return (new.to_string(), None);
+    }
+    if new == DEV_NULL {
+        return (old.to_string(), None);
+    }
+    match (old.split_once('/'), new.split_once('/')) {
+        (Some((_, old_path)), Some((_, new_path))) if old_path == new_path => {
+            (new_path.to_string(), None)
+        }
+        _ => (new.to_string(), Some(old.to_string())),
+    }
+}
+
+/// Checks if the path of a unified or context diff is a missing file,
+/// `/dev/null` or a file with the timestamp of the epoch
+fn is_missing(path: &RawPath<'_>) -> bool {
+    path.path == DEV_NULL
+        || path.timestamp.is_some_and(|timestamp| {
+            EPOCH_TIMESTAMPS
+                .iter()
+                .any(|epoch| timestamp.starts_with(epoch))
+        })
+}
+
+/// Converts a raw commit to a `Commit` without files, they are added while
+/// parsing the following sections
+fn get_commit(raw_commit: &RawCommit<'_>) -> Commit {
+    // the folded lines of a mail header are joined
+    let get_field = |keys: &[&str]| {
+        raw_commit
+            .fields
+            .iter()
+            .find(|(key, _)| keys.contains(key))
+            .map_or(String::new(), |(_, value)| {
+                value.split_whitespace().collect::<Vec<&str>>().join(" ")
+            })
+    };
+    let mut message = raw_commit.message.join("\n").trim_matches('\n').to_string();
+
+    // the subject of a mail is the first line of the message
+    let subject = get_field(&["Subject"]);
+    let (series, subject) = get_patch_subject(&subject);
+    if !subject.is_empty() {
+        message = if message.is_empty() {
+            subject.to_string()
+        } else {
+            format!("{}\n\n{}", subject, message)
+        };
+    }
+
+    Commit {
+        id: raw_commit.id.to_string(),
+        refs: raw_commit.refs.to_string(),
+        // `git log --format=fuller` and mails have other names of the fields
+        author: get_field(&["Author", "From"]),
+        date: get_field(&["Date", "AuthorDate"]),
+        message,
+        series,
+        files: Vec::new(),
+    }
+}
+
+/// Returns the number of the patch and the count of patches in the series
+/// and the subject without the `[PATCH 2/5]` prefix of `git format-patch`
+///
+/// # Arguments
+///
+/// * `subject` - the subject of the mail
+///
+fn get_patch_subject(subject: &str) -> (Option<(usize, usize)>, &str) {
+    let (prefix, subject) = match subject.strip_prefix('[').and_then(|s| s.split_once(']')) {
+        Some((prefix, subject)) => (prefix, subject.trim()),
+        None => return (None, subject),
+    };
+    // "[PATCH v2 2/5]", the series number is the only part with a slash
+    let series = prefix
+        .split_whitespace()
+        .filter_map(|part| part.split_once('/'))
+        .find_map(|(number, count)| Some((number.parse().ok()?, count.parse().ok()?)));
+    (series, subject)
+}
+
+/// Returns the position in the new filename of a header after its prefix.
+/// The prefixes of the options are used if both names start with them,
+/// otherwise the guessed prefixes.
+///
+/// # Arguments
+///
+/// * `header` - the header with the filenames
+/// * `options` - the options with the prefixes of `diff --git`
+///
+fn get_filename_start(header: &RawHeader<'_>, options: &ParseOptions) -> usize {
+    let (old, new) = &header.filenames;
+    match (header.prefixes, &options.prefixes) {
+        (Some(_), Some((src_prefix, dst_prefix)))
+            if old.starts_with(src_prefix.as_str()) && new.starts_with(dst_prefix.as_str()) =>
+        {
+            dst_prefix.len()
+        }
+        (Some((_, new_prefix)), _) => new_prefix,
+        (None, _) => 0,
+    }
+}
+
+/// Converts a raw file to a `File` with the numbers of every line, the
+/// parser has checked that they don't overflow
+///
+/// # Arguments
+///
+/// * `raw_file` - the parsed file
+/// * `options` - the options with the prefixes of `diff --git`
+///
+fn get_file(raw_file: &RawFile<'_>, options: &ParseOptions) -> File {
+    let start = get_filename_start(&raw_file.header, options);
+    let mut filename: String = raw_file.header.filenames.1[start..].to_string();
+    let mut old_filename: Option<String> = None;
+    let mut similarity: Option<usize> = None;
+    let mut modes: Option<(String, String)> = None;
+    let mut commit_id: String = "".to_string();
+    let mut modifier: MODIFIER = MODIFIER::MODIFIED;
+    let mut timestamps: (Option<String>, Option<String>) = (None, None);
+    if let Some((old, new)) = &raw_file.header.paths {
+        // the filenames of git are already without prefixes
+        if !raw_file.header.git {
+            let (new_filename, old_name) =
+                get_unified_filenames(&unquote_path(old.path), &unquote_path(new.path));
+            filename = new_filename;
+            old_filename = old_name;
+        }
+        if is_missing(old) {
+            modifier = MODIFIER::ADD;
+        } else if is_missing(new) {
+            modifier = MODIFIER::DELETE;
+        }
+        timestamps = (
+            old.timestamp.map(String::from),
+            new.timestamp.map(String::from),
+        );
+    }
+    for extended_header in &raw_file.header.extended_headers {
+        match extended_header {
+            ExtendedHeader::Index(index) => commit_id = index.to_string(),
+            ExtendedHeader::NewFile => modifier = MODIFIER::ADD,
+            ExtendedHeader::Deleted => modifier = MODIFIER::DELETE,
+            ExtendedHeader::RenameFile((from, to)) | ExtendedHeader::CopyFile((from, to)) => {
+                modifier = match extended_header {
+                    ExtendedHeader::CopyFile(_) => MODIFIER::COPIED,
+                    _ => MODIFIER::RENAMED,
+                };
+                old_filename = Some(unquote_path(from).to_string());
+                filename = unquote_path(to).to_string();
+            }
+            ExtendedHeader::ChMode((old_mode, new_mode)) => {
+                modes = Some((old_mode.to_string(), new_mode.to_string()))
+            }
+            ExtendedHeader::SimilarityIndex(index) => {
+                similarity = index.trim_end_matches('%').parse().ok()
+            }
+            _ => {}
+        }
+    }
+
+    let mut hunks: Vec<Hunk> = Vec::new();
+    for hunk in &raw_file.hunks {
+        let mut lines: Vec<LINE> = Vec::new();
+        let mut line_nr_left = hunk.line_info.0;
+        let mut line_nr_right = hunk.line_info.2;
+        let mut line_nrs_parents: Vec<u32> = hunk.parents.iter().map(|range| range.0).collect();
+
+        for line in &hunk.lines {
+            match line {
+                RawLine::Left(content) => {
+                    lines.push(LINE::REM {
+                        number: line_nr_left as usize,
+                        line: String::from(*content),
+                        no_newline: false,
+                    });
+                    line_nr_left += 1;
+                }
+                RawLine::Right(content) => {
+                    lines.push(LINE::ADD {
+                        number: line_nr_right as usize,
+                        line: String::from(*content),
+                        no_newline: false,
+                    });
+                    line_nr_right += 1;
+                }
+                RawLine::Both(content) => {
+                    lines.push(LINE::NOP {
+                        number_left: line_nr_left as usize,
+                        number_right: line_nr_right as usize,
+                        line: String::from(*content),
+                        no_newline: false,
+                    });
+                    line_nr_right += 1;
+                    line_nr_left += 1;
+                }
+                RawLine::Combined(changes, content) => {
+                    let parents: Vec<CHANGE> = changes
+                        .chars()
+                        .map(|change| match change {
+                            '+' => CHANGE::ADD,
+                            '-' => CHANGE::REM,
+                            _ => CHANGE::NOP,
+                        })
+                        .collect();
+                    let in_result = !parents.contains(&CHANGE::REM);
+                    let number = if in_result {
+                        line_nr_right
+                    } else {
+                        parents
+                            .iter()
+                            .position(|change| *change == CHANGE::REM)
+                            .and_then(|i| line_nrs_parents.get(i).copied())
+                            .unwrap_or(line_nr_left)
+                    };
+                    // a removed line is in the parents it is removed from,
+                    // a line of the result in the parents without a change
+                    for (line_nr, change) in line_nrs_parents.iter_mut().zip(&parents) {
+                        if *change == CHANGE::REM || (in_result && *change == CHANGE::NOP) {
+                            *line_nr += 1;
+                        }
+                    }
+                    if in_result {
+                        line_nr_right += 1;
+                    }
+                    lines.push(LINE::COMBINED {
+                        number: number as usize,
+                        parents,
+                        line: String::from(*content),
+                        no_newline: false,
+                    });
+                }
+                RawLine::NoNewline => match lines.last_mut() {
+                    Some(LINE::ADD { no_newline, .. })
+                    | Some(LINE::REM { no_newline, .. })
+                    | Some(LINE::NOP { no_newline, .. })
+                    | Some(LINE::COMBINED { no_newline, .. }) => *no_newline = true,
+                    None => {}
+                },
             }
         }
+        hunks.push(Hunk::new(
+            (
+                hunk.line_info.0 as usize,
+                hunk.line_info.1 as usize,
+                hunk.line_info.2 as usize,
+                hunk.line_info.3 as usize,
+            ),
+            hunk.heading.to_string(),
+            lines,
+        ));
+    }
+    let mut file = File::new(modifier, filename, commit_id, hunks);
+    file.old_filename = old_filename;
+    file.similarity = similarity;
+    file.old_timestamp = timestamps.0;
+    file.new_timestamp = timestamps.1;
+    if let Some((old_mode, new_mode)) = modes {
+        file.old_mode = Some(old_mode);
+        file.new_mode = Some(new_mode);
     }
+    // the patch has the forward block first and the reverse block second
+    file.binary = raw_file.binary.as_ref().map(|binary| match binary {
+        RawBinary::Differ => Binary {
+            old_size: None,
+            new_size: None,
+        },
+        RawBinary::Patch(blocks) => Binary {
+            old_size: blocks.get(1).map(get_binary_size),
+            new_size: blocks.first().map(get_binary_size),
+        },
+    });
+    file
+}
+
+/* --------------------------------------------------------- */
+/* ------------------------- TESTS ------------------------- */
+/* --------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn parse_raw_file_header_test() {
-        let input = r#"diff --git a/file2.txt b/file2.txt
-similarity index 80%
-dissimilarity index 20%
-index 2b2338d..43febe7 100644
---- a/file2.txt
-+++ b/file2.txt
-"#;
-        match parse_raw_file_header(&input[..]) {
-            Ok((_remaining, result)) => {
+    fn parse_git_file_header_test() {
+        let input = "diff --git a/script.sh b/script.sh\nindex 1234567..89abcde 100644\n--- a/script.sh\n+++ b/script.sh\n@@ -1 +1 @@\n";
+        match parse_git_file_header(input) {
+            Ok((remaining, result)) => {
+                assert_eq!("@@ -1 +1 @@\n", remaining);
                 assert_eq!(
-                    RawHeader {
-                        filenames: ("file2.txt", "file2.txt"),
-                        extended_headers: vec![
-                            ExtendedHeader::SimilarityIndex("80%"),
-                            ExtendedHeader::DissimilarityIndex("20%"),
-                            ExtendedHeader::Index("43febe7"),
-                        ]
-                    },
-                    result
+                    (Cow::Borrowed("a/script.sh"), Cow::Borrowed("b/script.sh")),
+                    result.filenames
                 );
+                assert_eq!(Some((2, 2)), result.prefixes);
+                assert_eq!(
+                    vec![ExtendedHeader::Index("89abcde")],
+                    result.extended_headers
+                );
+                assert!(result.git);
             }
             Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+                panic!("Error: {:?}", e);
             }
         }
     }
 
     #[test]
-    fn parse_lines_info_test() {
-        let input =
-            "--- a/file1.txt\n+++ b/file1.txt\n@@ -1,3 +1,3 @@ first content line of the file\n";
-        match parse_lines_info(&input[..]) {
-            Ok((remaining, result)) => {
-                assert!(remaining.is_empty());
-                assert_eq!((1, 3, 1, 3), result);
+    fn parse_extended_header_mode_test() {
+        let input = "old mode 100644\nnew mode 100755\n";
+        match parse_extended_header_mode(input) {
+            Ok((_remaining, result)) => {
+                assert_eq!(ExtendedHeader::ChMode(("100644", "100755")), result);
             }
             Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+                panic!("Error: {:?}", e);
             }
         }
     }
 
     #[test]
-    fn parse_line_is_both_test() {
-        let input = " This is a line\n";
-        match parse_line(&input[..]) {
-            Ok((remaining, result)) => {
-                assert!(remaining.is_empty());
-                assert_eq!(RawLine::Both("This is a line"), result);
+    fn parse_extended_header_deleted_test() {
+        let input = "deleted file mode 100644\n";
+        match parse_extended_header_deleted(input) {
+            Ok((_remaining, result)) => {
+                assert_eq!(ExtendedHeader::Deleted, result);
             }
             Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+                panic!("Error: {:?}", e);
             }
         }
     }
 
     #[test]
-    fn parse_line_is_left_test() {
-        let input = "-This is a line\n";
-        match parse_line(&input[..]) {
-            Ok((remaining, result)) => {
-                assert!(remaining.is_empty());
-                assert_eq!(RawLine::Left("This is a line"), result);
+    fn parse_extended_header_new_file_test() {
+        let input = "new file mode 100644\n";
+        match parse_extended_header_new_file(input) {
+            Ok((_remaining, result)) => {
+                assert_eq!(ExtendedHeader::NewFile, result);
             }
             Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+                panic!("Error: {:?}", e);
             }
         }
     }
 
     #[test]
-    fn parse_line_is_right_test() {
-        let input = "+This is a line\n";
-        match parse_line(&input[..]) {
-            Ok((remaining, result)) => {
-                assert!(remaining.is_empty());
-                assert_eq!(RawLine::Right("This is a line"), result);
+    fn parse_extended_header_copy_file_test() {
+        let input = "copy from path/to/file/a\ncopy to path/to/file/b\n";
+        match parse_extended_header_copy_file(input) {
+            Ok((_remaining, result)) => {
+                assert_eq!(
+                    ExtendedHeader::CopyFile(("path/to/file/a", "path/to/file/b")),
+                    result
+                );
             }
             Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+                panic!("Error: {:?}", e);
             }
         }
     }
 
     #[test]
-    fn parse_lines_test() {
-        let input = "+This is a line\n this is a both line\n-This is a left line\n Another Both!\n";
-        match parse_lines(&input[..]) {
+    fn parse_extended_header_rename_file_test() {
+        let input = "rename from path/to/file/a\nrename to path/to/file/b\n";
+        match parse_extended_header_rename_file(input) {
+            Ok((_remaining, result)) => {
+                assert_eq!(
+                    ExtendedHeader::RenameFile(("path/to/file/a", "path/to/file/b")),
+                    result
+                );
+            }
+            Err(e) => {
+                panic!("Error: {:?}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_extended_header_similarity_index_test() {
+        let input = "similarity index 80%\n";
+        match parse_extended_header_similarity_index(input) {
+            Ok((_remaining, result)) => {
+                assert_eq!(ExtendedHeader::SimilarityIndex("80%"), result);
+            }
+            Err(e) => {
+                panic!("Error: {:?}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_extended_header_dissimilarity_index_test() {
+        let input = "dissimilarity index 20%\n";
+        match parse_extended_header_dissimilarity_index(input) {
+            Ok((_remaining, result)) => {
+                assert_eq!(ExtendedHeader::DissimilarityIndex("20%"), result);
+            }
+            Err(e) => {
+                panic!("Error: {:?}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_extended_header_index_test() {
+        let input = "index 089fe5f..384ac88 100644\n@@";
+        match parse_extended_header_index(input) {
+            Ok((remaining, result)) => {
+                assert_eq!("@@", remaining);
+                assert_eq!(ExtendedHeader::Index("384ac88"), result);
+            }
+            Err(e) => {
+                panic!("Error: {:?}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_raw_file_header_test() {
+        let input = r#"diff --git a/file2.txt b/file2.txt
+similarity index 80%
+dissimilarity index 20%
+index 2b2338d..43febe7 100644
+--- a/file2.txt
++++ b/file2.txt
+"#;
+        match parse_raw_file_header(input) {
+            Ok((_remaining, result)) => {
+                assert_eq!(
+                    RawHeader {
+                        filenames: ("a/file2.txt".into(), "b/file2.txt".into()),
+                        prefixes: Some((2, 2)),
+                        extended_headers: vec![
+                            ExtendedHeader::SimilarityIndex("80%"),
+                            ExtendedHeader::DissimilarityIndex("20%"),
+                            ExtendedHeader::Index("43febe7"),
+                        ],
+                        paths: Some((
+                            RawPath {
+                                path: "a/file2.txt",
+                                timestamp: None
+                            },
+                            RawPath {
+                                path: "b/file2.txt",
+                                timestamp: None
+                            }
+                        )),
+                        git: true
+                    },
+                    result
+                );
+            }
+            Err(e) => {
+                panic!("Error: {:?}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_lines_info_test() {
+        let input = "@@ -1,3 +1,3 @@ first content line of the file\n";
+        match parse_lines_info(input) {
+            Ok((remaining, result)) => {
+                assert!(remaining.is_empty());
+                assert_eq!(((1, 3, 1, 3), "first content line of the file"), result);
+            }
+            Err(e) => {
+                panic!("Error: {:?}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_lines_info_without_counts_test() {
+        let inputs = vec![
+            ("@@ -3,2 +3,2 @@\n", ((3, 2, 3, 2), "")),
+            ("@@ -3 +3,2 @@\n", ((3, 1, 3, 2), "")),
+            ("@@ -3,2 +3 @@\n", ((3, 2, 3, 1), "")),
+            ("@@ -3 +3 @@\n", ((3, 1, 3, 1), "")),
+            ("@@ -0,0 +1 @@\n", ((0, 0, 1, 1), "")),
+            ("@@ -1 +0,0 @@\n", ((1, 1, 0, 0), "")),
+            ("@@ -3 +3 @@ fn main() {\n", ((3, 1, 3, 1), "fn main() {")),
+        ];
+        for (input, expected) in inputs {
+            match parse_lines_info(input) {
+                Ok((remaining, result)) => {
+                    assert!(remaining.is_empty());
+                    assert_eq!(expected, result, "{}", input);
+                }
+                Err(e) => panic!("Error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_content_single_line_hunk_test() {
+        let input = r#"diff --git a/script.sh b/script.sh
+index 4984ce4..47f2550 100644
+--- a/script.sh
++++ b/script.sh
+@@ -3 +3 @@
+-echo "Test"
++echo "Test is going on"
+"#;
+        let result = parse_content(input);
+        let expected = File::new(
+            MODIFIER::MODIFIED,
+            "script.sh".to_string(),
+            "47f2550".to_string(),
+            vec![Hunk::new(
+                (3, 1, 3, 1),
+                "".to_string(),
+                vec![
+                    LINE::REM {
+                        number: 3,
+                        line: "echo \"Test\"".to_string(),
+                        no_newline: false,
+                    },
+                    LINE::ADD {
+                        number: 3,
+                        line: "echo \"Test is going on\"".to_string(),
+                        no_newline: false,
+                    },
+                ],
+            )],
+        );
+        assert_eq!(vec![expected], result)
+    }
+
+    #[test]
+    fn parse_line_is_both_test() {
+        let input = " This is a line\n";
+        match parse_line(input) {
+            Ok((remaining, result)) => {
+                assert!(remaining.is_empty());
+                assert_eq!(RawLine::Both("This is a line"), result);
+            }
+            Err(e) => {
+                panic!("Error: {:?}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_line_is_left_test() {
+        let input = "-This is a line\n";
+        match parse_line(input) {
+            Ok((remaining, result)) => {
+                assert!(remaining.is_empty());
+                assert_eq!(RawLine::Left("This is a line"), result);
+            }
+            Err(e) => {
+                panic!("Error: {:?}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_line_is_right_test() {
+        let input = "+This is a line\n";
+        match parse_line(input) {
             Ok((remaining, result)) => {
                 assert!(remaining.is_empty());
+                assert_eq!(RawLine::Right("This is a line"), result);
+            }
+            Err(e) => {
+                panic!("Error: {:?}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_line_is_no_newline_test() {
+        let input = "\\ No newline at end of file\n";
+        match parse_line(input) {
+            Ok((remaining, result)) => {
+                assert!(remaining.is_empty());
+                assert_eq!(RawLine::NoNewline, result);
+            }
+            Err(e) => panic!("Error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn parse_content_no_newline_test() {
+        let input = r#"diff --git a/list.txt b/list.txt
+index 5005045..73ea95f 100644
+--- a/list.txt
++++ b/list.txt
+@@ -1,2 +1,2 @@
+ apples
+-oranges
+\ No newline at end of file
++oranges
+"#;
+        let result = parse_content(input);
+        let expected = File::new(
+            MODIFIER::MODIFIED,
+            "list.txt".to_string(),
+            "73ea95f".to_string(),
+            vec![Hunk::new(
+                (1, 2, 1, 2),
+                "".to_string(),
+                vec![
+                    LINE::NOP {
+                        number_left: 1,
+                        number_right: 1,
+                        line: "apples".to_string(),
+                        no_newline: false,
+                    },
+                    LINE::REM {
+                        number: 2,
+                        line: "oranges".to_string(),
+                        no_newline: true,
+                    },
+                    LINE::ADD {
+                        number: 2,
+                        line: "oranges".to_string(),
+                        no_newline: false,
+                    },
+                ],
+            )],
+        );
+        assert_eq!(vec![expected], result)
+    }
+
+    #[test]
+    fn parse_hunk_lines_test() {
+        let input = "+This is a line\n this is a both line\n-This is a left line\n Another Both!\n--- a/next.txt\n+++ b/next.txt\n";
+        // the lines end after the counts at the start of the next file
+        match parse_hunk_lines(input, (3, 3)) {
+            Ok((remaining, result)) => {
+                assert_eq!("--- a/next.txt\n+++ b/next.txt\n", remaining);
                 assert_eq!(
                     vec![
                         RawLine::Right("This is a line"),
@@ -528,17 +2461,14 @@ index 2b2338d..43febe7 100644
                 );
             }
             Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+                panic!("Error: {:?}", e);
             }
         }
     }
 
     #[test]
     fn parse_raw_file_hunk_test() {
-        let input = r#"--- a/file.txt
-+++ b/file.txt
-@@ -1,3 +1,6 @@
+        let input = r#"@@ -1,3 +1,6 @@
 +Add lines on top
 +More than one
 +So... three
@@ -546,12 +2476,15 @@ index 2b2338d..43febe7 100644
  very good expanded
  that it must break it
 "#;
-        match parse_raw_file_hunk(&input[..]) {
+        match parse_raw_file_hunk(input) {
             Ok((remaining, result)) => {
                 assert!(remaining.is_empty());
                 assert_eq!(
                     RawHunk {
+                        header: "@@ -1,3 +1,6 @@",
                         line_info: (1, 3, 1, 6),
+                        heading: "",
+                        parents: Vec::new(),
                         lines: vec![
                             RawLine::Right("Add lines on top"),
                             RawLine::Right("More than one"),
@@ -565,8 +2498,7 @@ index 2b2338d..43febe7 100644
                 );
             }
             Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+                panic!("Error: {:?}", e);
             }
         }
     }
@@ -586,17 +2518,33 @@ index c64d930..e475af3 100644
 +bananas
 +peaches
 "#;
-        match parse_raw_file(&input[..]) {
+        match parse_raw_file(input) {
             Ok((remaining, result)) => {
                 assert!(remaining.is_empty());
                 assert_eq!(
                     RawFile {
                         header: RawHeader {
-                            filenames: ("file.txt", "file.txt"),
-                            extended_headers: vec![ExtendedHeader::Index("e475af3")]
+                            filenames: ("a/file.txt".into(), "b/file.txt".into()),
+                            prefixes: Some((2, 2)),
+                            extended_headers: vec![ExtendedHeader::Index("e475af3")],
+                            paths: Some((
+                                RawPath {
+                                    path: "a/file.txt",
+                                    timestamp: None
+                                },
+                                RawPath {
+                                    path: "b/file.txt",
+                                    timestamp: None
+                                }
+                            )),
+                            git: true
                         },
+                        binary: None,
                         hunks: vec![RawHunk {
+                            header: "@@ -1,5 +1,5 @@",
                             line_info: (1, 5, 1, 5),
+                            heading: "",
+                            parents: Vec::new(),
                             lines: vec![
                                 RawLine::Both("apples"),
                                 RawLine::Both("pears"),
@@ -612,8 +2560,7 @@ index c64d930..e475af3 100644
                 );
             }
             Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+                panic!("Error: {:?}", e);
             }
         }
     }
@@ -642,17 +2589,33 @@ index c5d5782..5014215 100644
  whiped cream
 -
 "#;
-        match parse_raw_file(&input[..]) {
+        match parse_raw_file(input) {
             Ok((_remaining, result)) => {
                 assert_eq!(
                     RawFile {
                         header: RawHeader {
-                            filenames: ("file.txt", "file.txt"),
-                            extended_headers: vec![ExtendedHeader::Index("5014215")]
+                            filenames: ("a/file.txt".into(), "b/file.txt".into()),
+                            prefixes: Some((2, 2)),
+                            extended_headers: vec![ExtendedHeader::Index("5014215")],
+                            paths: Some((
+                                RawPath {
+                                    path: "a/file.txt",
+                                    timestamp: None
+                                },
+                                RawPath {
+                                    path: "b/file.txt",
+                                    timestamp: None
+                                }
+                            )),
+                            git: true
                         },
+                        binary: None,
                         hunks: vec![
                             RawHunk {
+                                header: "@@ -1,5 +1,4 @@",
                                 line_info: (1, 5, 1, 4),
+                                heading: "",
+                                parents: Vec::new(),
                                 lines: vec![
                                     RawLine::Both("apples"),
                                     RawLine::Left("pears"),
@@ -662,7 +2625,10 @@ index c5d5782..5014215 100644
                                 ]
                             },
                             RawHunk {
+                                header: "@@ -14,8 +13,7 @@ tomatoes",
                                 line_info: (14, 8, 13, 7),
+                                heading: "tomatoes",
+                                parents: Vec::new(),
                                 lines: vec![
                                     RawLine::Both("peas"),
                                     RawLine::Both("garlic"),
@@ -682,14 +2648,13 @@ index c5d5782..5014215 100644
                 );
             }
             Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
+                panic!("Error: {:?}", e);
             }
         }
     }
 
     #[test]
-    fn parse_multiple_raw_files_test() {
+    fn parse_sections_multiple_files_test() {
         let input = r#"diff --git a/fruits.txt b/fruits.txt
 index a4729d6..f3c9161 100644
 --- a/fruits.txt
@@ -712,50 +2677,60 @@ index db1afc7..6b65689 100644
 -gin
 +whisky
 "#;
-        match parse_raw_files_intern(&input[..]) {
-            Ok((_remaining, result)) => assert_eq!(
-                vec![
-                    RawFile {
-                        header: RawHeader {
-                            filenames: ("fruits.txt", "fruits.txt"),
-                            extended_headers: vec![ExtendedHeader::Index("f3c9161")]
-                        },
-                        hunks: vec![RawHunk {
-                            line_info: (1, 3, 1, 3),
-                            lines: vec![
-                                RawLine::Both("apples"),
-                                RawLine::Left("oranges"),
-                                RawLine::Both("bananas"),
-                                RawLine::Right("oranges"),
-                            ]
-                        }]
-                    },
-                    RawFile {
-                        header: RawHeader {
-                            filenames: ("spririts.txt", "spririts.txt"),
-                            extended_headers: vec![ExtendedHeader::Index("6b65689")]
-                        },
-                        hunks: vec![RawHunk {
-                            line_info: (1, 5, 1, 5),
-                            lines: vec![
-                                RawLine::Left("whisky"),
-                                RawLine::Right("gin"),
-                                RawLine::Both("rum"),
-                                RawLine::Both("tekila"),
-                                RawLine::Both("vodka"),
-                                RawLine::Left("gin"),
-                                RawLine::Right("whisky"),
-                            ]
-                        }]
-                    },
-                ],
-                result
-            ),
-            Err(e) => {
-                println!("Error: {:?}", e);
-                assert!(false)
-            }
-        }
+        let nop = |number_left, number_right, line: &str| LINE::NOP {
+            number_left,
+            number_right,
+            line: line.into(),
+            no_newline: false,
+        };
+        let rem = |number, line: &str| LINE::REM {
+            number,
+            line: line.into(),
+            no_newline: false,
+        };
+        let add = |number, line: &str| LINE::ADD {
+            number,
+            line: line.into(),
+            no_newline: false,
+        };
+        assert_eq!(
+            vec![
+                SECTION::FILE(File::new(
+                    MODIFIER::MODIFIED,
+                    "fruits.txt".into(),
+                    "f3c9161".into(),
+                    vec![Hunk::new(
+                        (1, 3, 1, 3),
+                        "".into(),
+                        vec![
+                            nop(1, 1, "apples"),
+                            rem(2, "oranges"),
+                            nop(3, 2, "bananas"),
+                            add(3, "oranges"),
+                        ]
+                    )]
+                )),
+                SECTION::FILE(File::new(
+                    MODIFIER::MODIFIED,
+                    "spririts.txt".into(),
+                    "6b65689".into(),
+                    vec![Hunk::new(
+                        (1, 5, 1, 5),
+                        "".into(),
+                        vec![
+                            rem(1, "whisky"),
+                            add(1, "gin"),
+                            nop(2, 2, "rum"),
+                            nop(3, 3, "tekila"),
+                            nop(4, 4, "vodka"),
+                            rem(5, "gin"),
+                            add(5, "whisky"),
+                        ]
+                    )]
+                )),
+            ],
+            parse_sections(input)
+        );
     }
 
     #[test]
@@ -771,46 +2746,55 @@ index 5005045..73ea95f 100644
  pineapples
 -kiwis
 +kiwi
-"#
-        .into();
-        let result = parse_content(&input);
+"#;
+        let result = parse_content(input);
         let expected = File::new(
             MODIFIER::MODIFIED,
             "list.txt".to_string(),
             "73ea95f".to_string(),
-            vec![Hunk::new(vec![
-                LINE::REM {
-                    number: 1,
-                    line: "apples".to_string(),
-                },
-                LINE::NOP {
-                    number_left: 2,
-                    number_right: 1,
-                    line: "oranges".to_string(),
-                },
-                LINE::ADD {
-                    number: 2,
-                    line: "pears".to_string(),
-                },
-                LINE::NOP {
-                    number_left: 3,
-                    number_right: 3,
-                    line: "pineapples".to_string(),
-                },
-                LINE::REM {
-                    number: 4,
-                    line: "kiwis".to_string(),
-                },
-                LINE::ADD {
-                    number: 4,
-                    line: "kiwi".to_string(),
-                },
-            ])],
+            vec![Hunk::new(
+                (1, 4, 1, 4),
+                "".to_string(),
+                vec![
+                    LINE::REM {
+                        number: 1,
+                        line: "apples".to_string(),
+                        no_newline: false,
+                    },
+                    LINE::NOP {
+                        number_left: 2,
+                        number_right: 1,
+                        line: "oranges".to_string(),
+                        no_newline: false,
+                    },
+                    LINE::ADD {
+                        number: 2,
+                        line: "pears".to_string(),
+                        no_newline: false,
+                    },
+                    LINE::NOP {
+                        number_left: 3,
+                        number_right: 3,
+                        line: "pineapples".to_string(),
+                        no_newline: false,
+                    },
+                    LINE::REM {
+                        number: 4,
+                        line: "kiwis".to_string(),
+                        no_newline: false,
+                    },
+                    LINE::ADD {
+                        number: 4,
+                        line: "kiwi".to_string(),
+                        no_newline: false,
+                    },
+                ],
+            )],
         );
         assert_eq!(vec![expected], result)
     }
 
-    #[test]
+    // #[test]
     // fn parse_content_2_test() {
     //     let input = r#"diff --git a/list.txt b/list.txt
     // index 5005045..73ea95f 100644
@@ -875,7 +2859,7 @@ index 5005045..73ea95f 100644
     //     );
     //     assert_eq!(vec![expected_file_1, expected_file_2], result)
     // }
-    #[test]
+    // #[test]
     // fn parse_content_renamed_file_test() {
     //     let input = r#"diff --git a/list.txt b/list_renamed.txt
     // similarity index 100%
@@ -923,55 +2907,1323 @@ diff --git a/list.txt b/list_renamed.txt
 similarity index 100%
 rename from list.txt
 rename to list_renamed.txt
-"#
-        .into();
-        let result = parse_content(&input);
+"#;
+        let result = parse_content(input);
         let expected_file_1 = File::new(
             MODIFIER::ADD,
             "list3.txt".into(),
             "33e4d8e".into(),
-            vec![Hunk::new(vec![
-                LINE::ADD {
-                    number: 1,
-                    line: "bananas".into(),
-                },
-                LINE::ADD {
-                    number: 2,
-                    line: "apples".into(),
-                },
-                LINE::ADD {
-                    number: 3,
-                    line: "oranges".into(),
-                },
-            ])],
+            vec![Hunk::new(
+                (0, 0, 1, 3),
+                "".to_string(),
+                vec![
+                    LINE::ADD {
+                        number: 1,
+                        line: "bananas".into(),
+                        no_newline: false,
+                    },
+                    LINE::ADD {
+                        number: 2,
+                        line: "apples".into(),
+                        no_newline: false,
+                    },
+                    LINE::ADD {
+                        number: 3,
+                        line: "oranges".into(),
+                        no_newline: false,
+                    },
+                ],
+            )],
         );
         let expected_file_2 = File::new(
             MODIFIER::DELETE,
             "list.txt".into(),
             "0000000".into(),
-            vec![Hunk::new(vec![
-                LINE::REM {
-                    number: 1,
-                    line: "oranges".into(),
-                },
-                LINE::REM {
-                    number: 2,
-                    line: "pears".into(),
-                },
-                LINE::REM {
-                    number: 3,
-                    line: "pineapples".into(),
-                },
-                LINE::REM {
-                    number: 4,
-                    line: "kiwi".into(),
-                },
-            ])],
+            vec![Hunk::new(
+                (1, 4, 0, 0),
+                "".to_string(),
+                vec![
+                    LINE::REM {
+                        number: 1,
+                        line: "oranges".into(),
+                        no_newline: false,
+                    },
+                    LINE::REM {
+                        number: 2,
+                        line: "pears".into(),
+                        no_newline: false,
+                    },
+                    LINE::REM {
+                        number: 3,
+                        line: "pineapples".into(),
+                        no_newline: false,
+                    },
+                    LINE::REM {
+                        number: 4,
+                        line: "kiwi".into(),
+                        no_newline: false,
+                    },
+                ],
+            )],
+        );
+        let mut expected_file_3 = File::new(
+            MODIFIER::RENAMED,
+            "list_renamed.txt".into(),
+            "".into(),
+            vec![],
         );
-        let expected_file_3 = File::new(MODIFIER::RENAMED, "list.txt".into(), "".into(), vec![]);
+        expected_file_3.old_filename = Some("list.txt".into());
+        expected_file_3.similarity = Some(100);
         assert_eq!(
             vec![expected_file_1, expected_file_2, expected_file_3],
             result
         )
     }
+
+    #[test]
+    fn parse_binary_patch_test() {
+        let input = "GIT binary patch\nliteral 33\nocmc~xEoVr|%u6h)WJu1>D@n~OVJOK+EK$fTR>;ZEOHVBV0L#J*9{>OV\n\ndelta 18\nZcmd1LNnuFJ%u6h)WJu1>D@n~O0RTP82EYIS\n\ndiff --git";
+        match parse_binary_patch(input) {
+            Ok((remaining, result)) => {
+                assert_eq!("diff --git", remaining);
+                assert_eq!(
+                    RawBinary::Patch(vec![("literal", 33), ("delta", 18)]),
+                    result
+                );
+            }
+            Err(e) => panic!("Error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn parse_content_binary_test() {
+        let result = parse_content(include_str!("../test/resources/binary.patch"));
+        let mut expected_file_1 =
+            File::new(MODIFIER::ADD, "added.bin".into(), "8c484bc".into(), vec![]);
+        expected_file_1.binary = Some(Binary {
+            old_size: None,
+            new_size: None,
+        });
+        let mut expected_file_2 = File::new(
+            MODIFIER::MODIFIED,
+            "image.png".into(),
+            "171dfe0".into(),
+            vec![],
+        );
+        expected_file_2.binary = expected_file_1.binary.clone();
+        let mut expected_file_3 = File::new(
+            MODIFIER::DELETE,
+            "removed.bin".into(),
+            "0000000".into(),
+            vec![],
+        );
+        expected_file_3.binary = expected_file_1.binary.clone();
+        assert_eq!(
+            vec![expected_file_1, expected_file_2, expected_file_3],
+            result
+        );
+    }
+
+    #[test]
+    fn parse_content_binary_patch_test() {
+        let result = parse_content(include_str!("../test/resources/binary_patch.patch"));
+        let sizes: Vec<Option<Binary>> = result.into_iter().map(|file| file.binary).collect();
+        assert_eq!(
+            vec![
+                Some(Binary {
+                    old_size: Some(BINARY_SIZE::LITERAL(0)),
+                    new_size: Some(BINARY_SIZE::LITERAL(6)),
+                }),
+                Some(Binary {
+                    old_size: Some(BINARY_SIZE::LITERAL(18)),
+                    new_size: Some(BINARY_SIZE::LITERAL(33)),
+                }),
+                Some(Binary {
+                    old_size: Some(BINARY_SIZE::LITERAL(11)),
+                    new_size: Some(BINARY_SIZE::LITERAL(0)),
+                }),
+            ],
+            sizes
+        );
+    }
+
+    #[test]
+    fn parse_content_copied_file_test() {
+        let input = r#"diff --git a/list.txt b/list_copy.txt
+similarity index 92%
+copy from list.txt
+copy to list_copy.txt
+index 5005045..73ea95f 100644
+--- a/list.txt
++++ b/list_copy.txt
+@@ -1 +1 @@
+-kiwis
++kiwi
+"#;
+        let result = parse_content(input);
+        assert_eq!(1, result.len());
+        assert_eq!(MODIFIER::COPIED, result[0].modifier);
+        assert_eq!("list_copy.txt", result[0].filename);
+        assert_eq!(Some("list.txt".to_string()), result[0].old_filename);
+        assert_eq!(Some(92), result[0].similarity);
+        assert_eq!("73ea95f", result[0].commit_id);
+    }
+
+    #[test]
+    fn parse_content_mode_change_test() {
+        let input = r#"diff --git a/script.sh b/script.sh
+old mode 100644
+new mode 100755
+"#;
+        let result = parse_content(input);
+        let mut expected = File::new(MODIFIER::MODIFIED, "script.sh".into(), "".into(), vec![]);
+        expected.old_mode = Some("100644".into());
+        expected.new_mode = Some("100755".into());
+        assert_eq!(vec![expected], result);
+    }
+
+    #[test]
+    fn parse_content_no_hunks_test() {
+        let result = parse_content(include_str!("../test/resources/no_hunks.patch"));
+
+        let empty_added = File::new(MODIFIER::ADD, "empty.txt".into(), "e69de29".into(), vec![]);
+        let mut binary = File::new(
+            MODIFIER::MODIFIED,
+            "image.png".into(),
+            "407bbd5".into(),
+            vec![],
+        );
+        binary.binary = Some(Binary {
+            old_size: None,
+            new_size: None,
+        });
+        let mut renamed = File::new(
+            MODIFIER::RENAMED,
+            "list_renamed.txt".into(),
+            "".into(),
+            vec![],
+        );
+        renamed.old_filename = Some("list.txt".into());
+        renamed.similarity = Some(100);
+        let mut mode_change = File::new(MODIFIER::MODIFIED, "script.sh".into(), "".into(), vec![]);
+        mode_change.old_mode = Some("100644".into());
+        mode_change.new_mode = Some("100755".into());
+        let empty_deleted = File::new(
+            MODIFIER::DELETE,
+            "empty_old.txt".into(),
+            "0000000".into(),
+            vec![],
+        );
+
+        assert_eq!(
+            vec![empty_added, binary, renamed, mode_change, empty_deleted],
+            result
+        );
+        assert!(result
+            .iter()
+            .all(|file| file.get_max_line_number_size() == 0));
+    }
+
+    #[test]
+    fn parse_sections_text_only_test() {
+        let input = "Fix typo\n\n    in the commit 2f1d3a4\ncommit 2f1d3a4\n";
+        assert_eq!(vec![SECTION::TEXT(input.into())], parse_sections(input));
+        assert_eq!(Vec::<SECTION>::new(), parse_sections(""));
+    }
+
+    #[test]
+    fn parse_sections_text_around_files_test() {
+        let file = "diff --git a/list.txt b/list.txt\nindex 1f2d3a4..73ea95f 100644\n--- a/list.txt\n+++ b/list.txt\n@@ -1 +1 @@\n-apples\n+pears\n";
+        let input = format!("commit 2f1d3a4\n\n{}not a diff\n{}", file, file);
+        let result = parse_sections(&input);
+
+        assert_eq!(4, result.len());
+        assert_eq!(SECTION::TEXT("commit 2f1d3a4\n\n".into()), result[0]);
+        assert_eq!(SECTION::TEXT("not a diff\n".into()), result[2]);
+        match (&result[1], &result[3]) {
+            (SECTION::FILE(first), SECTION::FILE(second)) => {
+                assert_eq!("list.txt", first.filename);
+                assert_eq!(first, second);
+            }
+            _ => panic!("Error: expected files, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn parse_sections_broken_file_test() {
+        let broken = "diff --git list.txt\n@@ -1 +1 @@\n-apples\n";
+        let file = "diff --git a/list.txt b/list.txt\n@@ -1 +1 @@\n-apples\n+pears\n";
+        let input = format!("{}{}", broken, file);
+        let result = parse_sections(&input);
+
+        assert_eq!(2, result.len());
+        assert_eq!(SECTION::TEXT(broken.into()), result[0]);
+        match &result[1] {
+            SECTION::FILE(file) => assert_eq!(2, file.hunks[0].content.len()),
+            section => panic!("Error: expected a file, got {:?}", section),
+        }
+    }
+
+    #[test]
+    fn parse_sections_broken_hunk_test() {
+        let input =
+            "diff --git a/list.txt b/list.txt\n@@ -1,3 +1,3 @@\n-apples\nnot a diff line\n+pears\n";
+        let result = parse_sections(input);
+
+        assert_eq!(2, result.len());
+        match &result[0] {
+            SECTION::FILE(file) => assert_eq!(1, file.hunks[0].content.len()),
+            section => panic!("Error: expected a file, got {:?}", section),
+        }
+        assert_eq!(SECTION::TEXT("not a diff line\n+pears\n".into()), result[1]);
+    }
+
+    #[test]
+    fn parse_sections_invalid_line_numbers_test() {
+        let input = "diff --git a/list.txt b/list.txt\n@@ -99999999999 +1 @@\n-apples\n";
+        let result = parse_sections(input);
+        match &result[0] {
+            SECTION::FILE(file) => assert!(file.hunks.is_empty()),
+            section => panic!("Error: expected a file, got {:?}", section),
+        }
+        assert_eq!(
+            SECTION::TEXT("@@ -99999999999 +1 @@\n-apples\n".into()),
+            result[1]
+        );
+    }
+
+    #[test]
+    fn parse_sections_line_number_overflow_test() {
+        let input =
+            "diff --git a/list.txt b/list.txt\n@@ -4294967295 +4294967295 @@\n apples\n pears\n";
+        let result = parse_sections(input);
+        match &result[0] {
+            SECTION::FILE(file) => assert!(file.hunks.is_empty()),
+            section => panic!("Error: expected a file, got {:?}", section),
+        }
+        assert_eq!(
+            SECTION::TEXT("@@ -4294967295 +4294967295 @@\n apples\n pears\n".into()),
+            result[1]
+        );
+        assert_eq!(
+            Err(ParseError::Unexpected {
+                line: 2,
+                file: "list.txt".into(),
+                expected: "extended header or hunk header"
+            }),
+            parse_strict(input)
+        );
+
+        let input = "*** a/list.txt\n--- b/list.txt\n***************\n*** 4294967295,4294967296 ****\n  apples\n--- 4294967295 ----\n";
+        match &parse_sections(input)[0] {
+            SECTION::FILE(file) => assert!(file.hunks.is_empty()),
+            section => panic!("Error: expected a file, got {:?}", section),
+        }
+
+        let input = "4294967295c4294967295\n< apples\n---\n> pears\n";
+        assert_eq!(vec![SECTION::TEXT(input.into())], parse_sections(input));
+        assert!(parse_strict(input).is_err());
+
+        // the last valid line number
+        let input = "diff --git a/list.txt b/list.txt\n@@ -4294967294 +4294967294 @@\n apples\n";
+        assert_eq!(1, parse_strict(input).unwrap()[0].hunks.len());
+    }
+
+    #[test]
+    fn parse_strict_test() {
+        let result = parse_strict(include_str!("../test/resources/no_hunks.patch"));
+        assert_eq!(
+            Ok(parse_content(include_str!(
+                "../test/resources/no_hunks.patch"
+            ))),
+            result
+        );
+        assert_eq!(Ok(vec![]), parse_strict(""));
+    }
+
+    #[test]
+    fn parse_strict_no_diff_test() {
+        let input = "commit 2f1d3a4\ndiff --git a/list.txt b/list.txt\n";
+        assert_eq!(Err(ParseError::NoDiff { line: 1 }), parse_strict(input));
+    }
+
+    #[test]
+    fn parse_strict_header_test() {
+        let input =
+            "diff --git a/list.txt b/list.txt\n@@ -1 +1 @@\n-apples\n+pears\ndiff --git list.txt\n";
+        let result = parse_strict(input);
+        assert_eq!(Err(ParseError::Header { line: 5, column: 1 }), result);
+        assert_eq!(
+            "line 5, column 1: expected file names",
+            result.unwrap_err().to_string()
+        );
+
+        let input = "diff --git a/list.txt\n";
+        assert_eq!(
+            Err(ParseError::Header { line: 1, column: 1 }),
+            parse_strict(input)
+        );
+    }
+
+    #[test]
+    fn parse_strict_unexpected_test() {
+        let input = "diff --git a/list.txt b/list.txt\nindex 1f2d3a4..73ea95f 100644\n--- a/list.txt\n+++ b/list.txt\n@@ -1 +1 @@\n-apples\n+pears\nnot a diff line\n";
+        let result = parse_strict(input);
+        assert_eq!(
+            Err(ParseError::Unexpected {
+                line: 8,
+                file: "list.txt".into(),
+                expected: "hunk line or hunk header"
+            }),
+            result
+        );
+        assert_eq!(
+            "line 8 in list.txt: expected hunk line or hunk header",
+            result.unwrap_err().to_string()
+        );
+
+        let input = "diff --git a/list.txt b/list.txt\nindex 1f2d3a4..73ea95f 100644\n@@ -1 +1\n";
+        assert_eq!(
+            Err(ParseError::Unexpected {
+                line: 3,
+                file: "list.txt".into(),
+                expected: "extended header or hunk header"
+            }),
+            parse_strict(input)
+        );
+    }
+
+    #[test]
+    fn parse_strict_line_count_test() {
+        let input = "diff --git a/list.txt b/list.txt\n@@ -1 +1 @@\n-apples\n+pears\n@@ -5,3 +5,3 @@ fruits\n apples\n-pears\n+plums\n";
+        let result = parse_strict(input);
+        assert_eq!(
+            Err(ParseError::LineCount {
+                line: 5,
+                file: "list.txt".into(),
+                expected: (3, 3),
+                found: (2, 2)
+            }),
+            result
+        );
+        assert_eq!(
+            "line 5 in list.txt: expected hunk with 3 old and 3 new lines, found 2 and 2",
+            result.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn parse_strict_normal_line_count_test() {
+        let input = "1c1\n< apples\n---\n> pears\n3,4c3\n< bananas\n---\n> plums\n";
+        assert_eq!(
+            Err(ParseError::LineCount {
+                line: 5,
+                file: "".into(),
+                expected: (2, 1),
+                found: (1, 1)
+            }),
+            parse_strict(input)
+        );
+    }
+
+    #[test]
+    fn parse_unified_file_header_test() {
+        let input = "diff -ruN dir1/list.txt dir2/list.txt\n--- dir1/list.txt\t2019-03-04 10:21:31.000000000 +0000\n+++ dir2/list.txt\t2019-03-04 10:25:12.000000000 +0000\n";
+        match parse_unified_file_header(input) {
+            Ok((remaining, result)) => {
+                assert!(remaining.is_empty());
+                assert_eq!(
+                    RawHeader {
+                        filenames: ("dir1/list.txt".into(), "dir2/list.txt".into()),
+                        prefixes: None,
+                        extended_headers: vec![],
+                        paths: Some((
+                            RawPath {
+                                path: "dir1/list.txt",
+                                timestamp: Some("2019-03-04 10:21:31.000000000 +0000")
+                            },
+                            RawPath {
+                                path: "dir2/list.txt",
+                                timestamp: Some("2019-03-04 10:25:12.000000000 +0000")
+                            }
+                        )),
+                        git: false
+                    },
+                    result
+                );
+            }
+            Err(e) => {
+                panic!("Error: {:?}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn get_unified_filenames_test() {
+        let inputs = vec![
+            (("dir1/list.txt", "dir2/list.txt"), ("list.txt", None)),
+            (("a/src/main.rs", "b/src/main.rs"), ("src/main.rs", None)),
+            (("/dev/null", "new.txt"), ("new.txt", None)),
+            (("old.txt", "/dev/null"), ("old.txt", None)),
+            (("list.txt", "list.txt"), ("list.txt", None)),
+            (("old.txt", "new.txt"), ("new.txt", Some("old.txt"))),
+            (("a/old.txt", "b/new.txt"), ("b/new.txt", Some("a/old.txt"))),
+        ];
+        for ((old, new), (filename, old_filename)) in inputs {
+            assert_eq!(
+                (filename.to_string(), old_filename.map(String::from)),
+                get_unified_filenames(old, new)
+            );
+        }
+    }
+
+    #[test]
+    fn parse_content_unified_dirs_test() {
+        let result = parse_strict(include_str!("../test/resources/unified_dirs.patch")).unwrap();
+
+        assert_eq!(4, result.len());
+        assert_eq!(MODIFIER::MODIFIED, result[0].modifier);
+        assert_eq!("list.txt", result[0].filename);
+        assert_eq!(None, result[0].old_filename);
+        assert_eq!(
+            Some("2019-03-04 10:21:31.000000000 +0000".to_string()),
+            result[0].old_timestamp
+        );
+        assert_eq!(
+            Some("2019-03-04 10:25:12.000000000 +0000".to_string()),
+            result[0].new_timestamp
+        );
+        assert_eq!((1, 3, 1, 4), result[0].hunks[0].line_info);
+        assert_eq!(MODIFIER::ADD, result[1].modifier);
+        assert_eq!("new.txt", result[1].filename);
+        assert_eq!(MODIFIER::DELETE, result[2].modifier);
+        assert_eq!("old.txt", result[2].filename);
+        assert_eq!("sub/dashes.txt", result[3].filename);
+        assert_eq!(
+            vec![
+                LINE::REM {
+                    number: 1,
+                    line: "-- old".into(),
+                    no_newline: false,
+                },
+                LINE::ADD {
+                    number: 1,
+                    line: "++ new".into(),
+                    no_newline: false,
+                },
+                LINE::NOP {
+                    number_left: 2,
+                    number_right: 2,
+                    line: "keep".into(),
+                    no_newline: false,
+                },
+            ],
+            result[3].hunks[0].content
+        );
+    }
+
+    #[test]
+    fn parse_content_unified_test() {
+        let result = parse_strict(include_str!("../test/resources/unified.patch")).unwrap();
+
+        assert_eq!(2, result.len());
+        assert_eq!("sub/dashes.txt", result[0].filename);
+        assert_eq!(3, result[0].hunks[0].content.len());
+        assert_eq!("list.txt", result[1].filename);
+        assert_eq!(4, result[1].hunks[0].content.len());
+    }
+
+    #[test]
+    fn parse_content_unified_dev_null_test() {
+        let input = "--- /dev/null\n+++ new.txt\n@@ -0,0 +1 @@\n+new file\n--- old.txt\n+++ /dev/null\n@@ -1 +0,0 @@\n-gone\n";
+        let result = parse_content(input);
+
+        assert_eq!(2, result.len());
+        assert_eq!(MODIFIER::ADD, result[0].modifier);
+        assert_eq!("new.txt", result[0].filename);
+        assert_eq!(None, result[0].old_timestamp);
+        assert_eq!(MODIFIER::DELETE, result[1].modifier);
+        assert_eq!("old.txt", result[1].filename);
+    }
+
+    #[test]
+    fn parse_context_hunk_test() {
+        let input = r#"*************** fn main() {
+*** 1,5 ****
+  apples
+! pears
+  bananas
+- cherries
+  dates
+--- 1,5 ----
+  apples
+! plums
+! kiwis
+  bananas
+  dates
+"#;
+        match parse_context_hunk(input) {
+            Ok((remaining, result)) => {
+                assert!(remaining.is_empty());
+                assert_eq!(
+                    RawHunk {
+                        header: "*************** fn main() {",
+                        line_info: (1, 5, 1, 5),
+                        heading: "fn main() {",
+                        parents: Vec::new(),
+                        lines: vec![
+                            RawLine::Both("apples"),
+                            RawLine::Left("pears"),
+                            RawLine::Right("plums"),
+                            RawLine::Right("kiwis"),
+                            RawLine::Both("bananas"),
+                            RawLine::Left("cherries"),
+                            RawLine::Both("dates"),
+                        ]
+                    },
+                    result
+                );
+            }
+            Err(e) => {
+                panic!("Error: {:?}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_context_hunk_left_out_lines_test() {
+        let inputs = vec![
+            (
+                "***************\n*** 8,9 ****\n--- 8,10 ----\n  kiwis\n+ melons\n  mangos\n",
+                RawHunk {
+                    header: "***************",
+                    line_info: (8, 2, 8, 3),
+                    heading: "",
+                    parents: Vec::new(),
+                    lines: vec![
+                        RawLine::Both("kiwis"),
+                        RawLine::Right("melons"),
+                        RawLine::Both("mangos"),
+                    ],
+                },
+            ),
+            (
+                "***************\n*** 1,2 ****\n  fn main() {\n-     b();\n--- 1 ----\n",
+                RawHunk {
+                    header: "***************",
+                    line_info: (1, 2, 1, 1),
+                    heading: "",
+                    parents: Vec::new(),
+                    lines: vec![RawLine::Both("fn main() {"), RawLine::Left("    b();")],
+                },
+            ),
+            (
+                "***************\n*** 0 ****\n--- 1 ----\n+ new file\n",
+                RawHunk {
+                    header: "***************",
+                    line_info: (0, 0, 1, 1),
+                    heading: "",
+                    parents: Vec::new(),
+                    lines: vec![RawLine::Right("new file")],
+                },
+            ),
+        ];
+        for (input, expected) in inputs {
+            match parse_context_hunk(input) {
+                Ok((remaining, result)) => {
+                    assert!(remaining.is_empty());
+                    assert_eq!(expected, result, "{}", input);
+                }
+                Err(e) => panic!("Error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn get_position_test() {
+        let input = "diff --git a/list.txt b/list.txt\n@@ -1 +1 @@ fruits\n";
+        assert_eq!((1, 1), get_position(input, 0));
+        assert_eq!((2, 13), get_position(input, 45));
+        assert_eq!((3, 1), get_position(input, input.len() + 10));
+
+        assert_eq!(Some(45), get_offset(input, &input[45..51]));
+        assert_eq!(None, get_offset(input, &String::from("fruits")));
+        assert_eq!(None, get_offset(&input[1..], &input[..2]));
+    }
+
+    #[test]
+    fn is_file_start_test() {
+        assert!(is_file_start("diff -c a.txt b.txt\n"));
+        assert!(is_file_start("--- a.txt\n+++ b.txt\n"));
+        assert!(is_file_start("*** a.txt\n--- b.txt\n"));
+        assert!(!is_file_start("*** 0 ****\n--- 1 ----\n"));
+        assert!(!is_file_start("--- a.txt\n--- b.txt\n"));
+        assert!(!is_file_start("commit 2f1d3a4\n"));
+        assert!(is_file_start("2c2\n< pears\n---\n> plums\n"));
+        assert!(is_file_start("10a11\n> melons\n"));
+        assert!(!is_file_start("1a2\nnot a diff\n"));
+    }
+
+    #[test]
+    fn parse_content_context_test() {
+        let result = parse_strict(include_str!("../test/resources/context.patch")).unwrap();
+
+        assert_eq!(4, result.len());
+        assert_eq!("eof.txt", result[0].filename);
+        assert_eq!(
+            vec![
+                LINE::REM {
+                    number: 1,
+                    line: "no newline".into(),
+                    no_newline: true,
+                },
+                LINE::ADD {
+                    number: 1,
+                    line: "no newline".into(),
+                    no_newline: false,
+                },
+            ],
+            result[0].hunks[0].content
+        );
+        assert_eq!("list.txt", result[1].filename);
+        assert_eq!(
+            Some("Mon Mar  4 10:21:31 2019".to_string()),
+            result[1].old_timestamp
+        );
+        assert_eq!(2, result[1].hunks.len());
+        assert_eq!((1, 5, 1, 5), result[1].hunks[0].line_info);
+        assert_eq!((8, 4, 8, 5), result[1].hunks[1].line_info);
+        assert_eq!(MODIFIER::MODIFIED, result[2].modifier);
+        assert_eq!((1, 4, 1, 3), result[2].hunks[0].line_info);
+        assert_eq!(MODIFIER::ADD, result[3].modifier);
+        assert_eq!("new.txt", result[3].filename);
+    }
+
+    #[test]
+    fn parse_normal_hunk_test() {
+        let inputs = vec![
+            (
+                "2,3c2\n< pears\n< bananas\n---\n> plums\n",
+                RawHunk {
+                    header: "2,3c2",
+                    line_info: (2, 2, 2, 1),
+                    heading: "",
+                    parents: Vec::new(),
+                    lines: vec![
+                        RawLine::Left("pears"),
+                        RawLine::Left("bananas"),
+                        RawLine::Right("plums"),
+                    ],
+                },
+            ),
+            (
+                "10a11,12\n> melons\n> kiwis\n",
+                RawHunk {
+                    header: "10a11,12",
+                    line_info: (10, 0, 11, 2),
+                    heading: "",
+                    parents: Vec::new(),
+                    lines: vec![RawLine::Right("melons"), RawLine::Right("kiwis")],
+                },
+            ),
+            (
+                "3d2\n<     b();\n",
+                RawHunk {
+                    header: "3d2",
+                    line_info: (3, 1, 2, 0),
+                    heading: "",
+                    parents: Vec::new(),
+                    lines: vec![RawLine::Left("    b();")],
+                },
+            ),
+        ];
+        for (input, expected) in inputs {
+            match parse_normal_hunk(input) {
+                Ok((remaining, result)) => {
+                    assert!(remaining.is_empty());
+                    assert_eq!(expected, result, "{}", input);
+                }
+                Err(e) => panic!("Error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_content_normal_test() {
+        let result = parse_strict(include_str!("../test/resources/normal.patch")).unwrap();
+
+        assert_eq!(1, result.len());
+        assert_eq!("", result[0].filename);
+        assert_eq!(2, result[0].hunks.len());
+        assert_eq!(
+            vec![
+                LINE::REM {
+                    number: 2,
+                    line: "pears".into(),
+                    no_newline: false,
+                },
+                LINE::ADD {
+                    number: 2,
+                    line: "plums".into(),
+                    no_newline: false,
+                },
+            ],
+            result[0].hunks[0].content
+        );
+        assert_eq!(
+            vec![LINE::ADD {
+                number: 11,
+                line: "melons".into(),
+                no_newline: false,
+            }],
+            result[0].hunks[1].content
+        );
+    }
+
+    #[test]
+    fn parse_content_normal_dirs_test() {
+        let result = parse_strict(include_str!("../test/resources/normal_dirs.patch")).unwrap();
+        let filenames: Vec<&str> = result.iter().map(|file| file.filename.as_str()).collect();
+
+        assert_eq!(vec!["eof.txt", "list.txt", "main.rs", "new.txt"], filenames);
+        assert_eq!(
+            vec![
+                LINE::REM {
+                    number: 1,
+                    line: "no newline".into(),
+                    no_newline: true,
+                },
+                LINE::ADD {
+                    number: 1,
+                    line: "no newline".into(),
+                    no_newline: false,
+                },
+            ],
+            result[0].hunks[0].content
+        );
+        assert_eq!((3, 1, 2, 0), result[2].hunks[0].line_info);
+    }
+
+    #[test]
+    fn parse_combined_hunk_test() {
+        let input = "@@@ -1,2 -1,2 +1,2 @@@ fn main() {\n  a\n- b\n -c\n++d\n";
+        assert_eq!(
+            Ok((
+                "",
+                RawHunk {
+                    header: "@@@ -1,2 -1,2 +1,2 @@@ fn main() {",
+                    line_info: (1, 2, 1, 2),
+                    heading: "fn main() {",
+                    parents: vec![(1, 2), (1, 2)],
+                    lines: vec![
+                        RawLine::Combined("  ", "a"),
+                        RawLine::Combined("- ", "b"),
+                        RawLine::Combined(" -", "c"),
+                        RawLine::Combined("++", "d"),
+                    ],
+                }
+            )),
+            parse_combined_hunk(input)
+        );
+    }
+
+    #[test]
+    fn parse_combined_hunk_three_parents_test() {
+        let input =
+            "@@@@ -1,3 -1,3 -1,3 +1,3 @@@@ fn main() {\n   a\n-  b\n - c\n  -d\n+++e\n   f\n";
+        assert_eq!(
+            Ok((
+                "",
+                RawHunk {
+                    header: "@@@@ -1,3 -1,3 -1,3 +1,3 @@@@ fn main() {",
+                    line_info: (1, 3, 1, 3),
+                    heading: "fn main() {",
+                    parents: vec![(1, 3), (1, 3), (1, 3)],
+                    lines: vec![
+                        RawLine::Combined("   ", "a"),
+                        RawLine::Combined("-  ", "b"),
+                        RawLine::Combined(" - ", "c"),
+                        RawLine::Combined("  -", "d"),
+                        RawLine::Combined("+++", "e"),
+                        RawLine::Combined("   ", "f"),
+                    ],
+                }
+            )),
+            parse_combined_hunk(input)
+        );
+
+        // the counts of the first parent and the result are checked
+        let input = format!("diff --cc main.rs\nindex 1234567,89abcde,fedcba9..7654321\n--- a/main.rs\n+++ b/main.rs\n{}", input);
+        let result = parse_strict(&input).unwrap();
+        let lines: Vec<(usize, Vec<CHANGE>)> = result[0].hunks[0]
+            .content
+            .iter()
+            .map(|line| match line {
+                LINE::COMBINED {
+                    number, parents, ..
+                } => (*number, parents.clone()),
+                _ => panic!("no combined line: {:?}", line),
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (1, vec![CHANGE::NOP, CHANGE::NOP, CHANGE::NOP]),
+                (2, vec![CHANGE::REM, CHANGE::NOP, CHANGE::NOP]),
+                (2, vec![CHANGE::NOP, CHANGE::REM, CHANGE::NOP]),
+                (2, vec![CHANGE::NOP, CHANGE::NOP, CHANGE::REM]),
+                (2, vec![CHANGE::ADD, CHANGE::ADD, CHANGE::ADD]),
+                (3, vec![CHANGE::NOP, CHANGE::NOP, CHANGE::NOP]),
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn parse_raw_file_header_combined_test() {
+        let input =
+            "diff --cc main.rs\nindex 754b7e9,2920335..7f77544\nmode 100644,100644..100755\n";
+        assert_eq!(
+            Ok((
+                "",
+                RawHeader {
+                    filenames: ("main.rs".into(), "main.rs".into()),
+                    prefixes: None,
+                    extended_headers: vec![
+                        ExtendedHeader::Index("7f77544"),
+                        ExtendedHeader::ChMode(("100644,100644", "100755")),
+                    ],
+                    paths: None,
+                    git: true,
+                }
+            )),
+            parse_raw_file_header(input)
+        );
+    }
+
+    #[test]
+    fn parse_content_combined_test() {
+        let result = parse_strict(include_str!("../test/resources/combined.patch")).unwrap();
+
+        assert_eq!(1, result.len());
+        assert_eq!("main.rs", result[0].filename);
+        assert!(result[0].is_combined());
+        let lines: Vec<(usize, Vec<CHANGE>)> = result[0].hunks[0]
+            .content
+            .iter()
+            .map(|line| match line {
+                LINE::COMBINED {
+                    number, parents, ..
+                } => (*number, parents.clone()),
+                _ => panic!("no combined line: {:?}", line),
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (1, vec![CHANGE::NOP, CHANGE::NOP]),
+                (2, vec![CHANGE::NOP, CHANGE::NOP]),
+                (3, vec![CHANGE::REM, CHANGE::NOP]),
+                (3, vec![CHANGE::NOP, CHANGE::REM]),
+                (3, vec![CHANGE::ADD, CHANGE::ADD]),
+                (4, vec![CHANGE::NOP, CHANGE::NOP]),
+                (5, vec![CHANGE::ADD, CHANGE::ADD]),
+                (6, vec![CHANGE::NOP, CHANGE::NOP]),
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn parse_commit_test() {
+        let input = "commit 4644f8d (HEAD -> main)\nMerge: 96ac013 edef6a9\nAuthor: Jane Doe <jane@example.com>\nDate:   Sat Mar 2 10:00:00 2019 +0100\n\n    Fix the parser\n    \n    It panicked.\n\ndiff --git a/a.txt b/a.txt\n";
+        assert_eq!(
+            Ok((
+                "diff --git a/a.txt b/a.txt\n",
+                RawCommit {
+                    id: "4644f8d",
+                    refs: "(HEAD -> main)",
+                    fields: vec![
+                        ("Merge", "96ac013 edef6a9"),
+                        ("Author", "Jane Doe <jane@example.com>"),
+                        ("Date", "Sat Mar 2 10:00:00 2019 +0100"),
+                    ],
+                    message: vec!["", "Fix the parser", "", "It panicked.", ""],
+                }
+            )),
+            parse_commit(input)
+        );
+        assert!(parse_commit("commit the changes\n").is_err());
+    }
+
+    #[test]
+    fn parse_sections_log_test() {
+        let result = parse_sections(include_str!("../test/resources/log.patch"));
+
+        assert_eq!(2, result.len());
+        match &result[0] {
+            SECTION::COMMIT(commit) => {
+                assert_eq!("406ed27f5743101999fd32d280b3dcf06ae80bec", commit.id);
+                assert_eq!("(HEAD -> main)", commit.refs);
+                assert_eq!("Jane Doe <jane@example.com>", commit.author);
+                assert_eq!("Sun Mar 3 10:00:00 2019 +0100", commit.date);
+                assert_eq!("Replace the pears\n\nPlums are in season.", commit.message);
+                let filenames: Vec<&str> = commit
+                    .files
+                    .iter()
+                    .map(|file| file.filename.as_str())
+                    .collect();
+                assert_eq!(vec!["list.txt", "main.rs"], filenames);
+            }
+            section => panic!("no commit: {:?}", section),
+        }
+        match &result[1] {
+            SECTION::COMMIT(commit) => {
+                assert_eq!("Add the list", commit.message);
+                assert_eq!(1, commit.files.len());
+            }
+            section => panic!("no commit: {:?}", section),
+        }
+        assert_eq!(
+            3,
+            parse_strict(include_str!("../test/resources/log.patch"))
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn parse_patch_test() {
+        let input = "From 4644f8d Mon Sep 17 00:00:00 2001\nFrom: Jane Doe <jane@example.com>\nDate: Sat, 2 Mar 2019 10:00:00 +0100\nSubject: [PATCH 2/5] Fix the parser for\n the long subject\n\nIt panicked.\n---\n a.txt | 2 +-\n 1 file changed\n\ndiff --git a/a.txt b/a.txt\n";
+        assert_eq!(
+            Ok((
+                "diff --git a/a.txt b/a.txt\n",
+                RawCommit {
+                    id: "4644f8d",
+                    refs: "",
+                    fields: vec![
+                        ("From", "Jane Doe <jane@example.com>"),
+                        ("Date", "Sat, 2 Mar 2019 10:00:00 +0100"),
+                        (
+                            "Subject",
+                            "[PATCH 2/5] Fix the parser for\n the long subject"
+                        ),
+                    ],
+                    message: vec!["It panicked."],
+                }
+            )),
+            parse_patch(input)
+        );
+        assert_eq!(Ok(("", "2.39.5")), parse_patch_signature("-- \n2.39.5\n\n"));
+    }
+
+    #[test]
+    fn get_patch_subject_test() {
+        assert_eq!(
+            (Some((2, 5)), "Fix the parser"),
+            get_patch_subject("[PATCH v2 2/5] Fix the parser")
+        );
+        assert_eq!(
+            (None, "Fix the parser"),
+            get_patch_subject("[PATCH] Fix the parser")
+        );
+        assert_eq!(
+            (None, "Fix the parser"),
+            get_patch_subject("Fix the parser")
+        );
+    }
+
+    #[test]
+    fn parse_sections_series_test() {
+        let result = parse_sections(include_str!("../test/resources/series.patch"));
+
+        assert_eq!(2, result.len());
+        let commits: Vec<&Commit> = result
+            .iter()
+            .map(|section| match section {
+                SECTION::COMMIT(commit) => commit,
+                section => panic!("no commit: {:?}", section),
+            })
+            .collect();
+        assert_eq!(Some((1, 2)), commits[0].series);
+        assert_eq!("Add the list", commits[0].message);
+        assert_eq!(1, commits[0].files.len());
+        assert_eq!(Some((2, 2)), commits[1].series);
+        assert_eq!("Jane Doe <jane@example.com>", commits[1].author);
+        assert_eq!("Sun, 3 Mar 2019 10:00:00 +0100", commits[1].date);
+        assert_eq!(
+            "Replace the pears\n\nPlums are in season.",
+            commits[1].message
+        );
+        assert_eq!(2, commits[1].files.len());
+        assert_eq!(
+            3,
+            parse_strict(include_str!("../test/resources/series.patch"))
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn stream_parser_test() {
+        let inputs = vec![
+            include_str!("../test/resources/log.patch"),
+            include_str!("../test/resources/series.patch"),
+            include_str!("../test/resources/unified.patch"),
+            include_str!("../test/resources/context.patch"),
+            include_str!("../test/resources/normal_dirs.patch"),
+            include_str!("../test/resources/normal.patch"),
+            "some text\nbefore\n",
+            "some text\n--- no path\n2c2\n< apples\n---\n> pears\n",
+        ];
+
+        for input in inputs {
+            let mut stream_parser = StreamParser::new();
+            let mut result: Vec<SECTION> = Vec::new();
+            for line in input.split_inclusive('\n') {
+                result.extend(stream_parser.push_line(line));
+            }
+            let streamed = result.len();
+            result.extend(stream_parser.finish());
+            // the text is returned line by line
+            let result = result.into_iter().fold(Vec::new(), |mut result, section| {
+                match (result.last_mut(), section) {
+                    (Some(SECTION::TEXT(last_text)), SECTION::TEXT(text)) => {
+                        last_text.push_str(&text)
+                    }
+                    (_, section) => result.push(section),
+                }
+                result
+            });
+
+            // the files of a commit follow it as own sections
+            let expected: Vec<SECTION> = parse_sections(input)
+                .into_iter()
+                .flat_map(|section| match section {
+                    SECTION::COMMIT(mut commit) => {
+                        let files = std::mem::take(&mut commit.files);
+                        std::iter::once(SECTION::COMMIT(commit))
+                            .chain(files.into_iter().map(SECTION::FILE))
+                            .collect()
+                    }
+                    section => vec![section],
+                })
+                .collect();
+            assert_eq!(expected, result);
+            if expected.len() > 1 {
+                assert!(streamed > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn stream_parser_text_test() {
+        let mut stream_parser = StreamParser::new();
+        assert_eq!(
+            vec![SECTION::TEXT("hello\n".into())],
+            stream_parser.push_line("hello\n")
+        );
+        // the empty lines and the header may be the start of a commit
+        for line in [
+            "\n",
+            "From 4644f8d Mon Sep 17 00:00:00 2001\n",
+            "From: Jane Doe <jane@example.com>\n",
+        ] {
+            assert!(stream_parser.push_line(line).is_empty());
+        }
+        for line in [
+            "Date: Sat, 2 Mar 2019 10:00:00 +0100\n",
+            "Subject: [PATCH] Fix it\n",
+            "\n",
+            "---\n",
+        ] {
+            assert!(stream_parser.push_line(line).is_empty());
+        }
+        let sections = stream_parser.push_line("diff --git a/a.txt b/a.txt\n");
+        assert_eq!(1, sections.len());
+        assert!(matches!(sections[0], SECTION::COMMIT(_)));
+
+        // a possible file start is text after the lines to know it
+        let mut stream_parser = StreamParser::new();
+        assert!(stream_parser.push_line("--- no path\n").is_empty());
+        assert!(stream_parser.push_line("bye\n").is_empty());
+        assert!(stream_parser.push_line("\n").is_empty());
+        assert_eq!(
+            vec![SECTION::TEXT("--- no path\nbye\n\nagain\n".into())],
+            stream_parser.push_line("again\n")
+        );
+    }
+
+    #[test]
+    fn stream_parser_hunk_lines_test() {
+        let mut stream_parser = StreamParser::new();
+        let header = "diff --git a/list.patch b/list.patch\n--- a/list.patch\n+++ b/list.patch\n";
+        for line in header.split_inclusive('\n') {
+            assert!(stream_parser.push_line(line).is_empty());
+        }
+        assert!(stream_parser.push_line("@@ -1,2 +1,2 @@\n").is_empty());
+        let hunk_start = stream_parser.buffer.len();
+        // a removed `-- ` and an added `++ ` line are no file start
+        for line in ["--- apples\n", "+++ pears\n", "--- plums\n", "+++ kiwis\n"] {
+            assert!(stream_parser.push_line(line).is_empty());
+            assert!(stream_parser
+                .starts
+                .iter()
+                .all(|(offset, _)| *offset < hunk_start));
+        }
+        assert_eq!((0, 0), stream_parser.hunk_lines);
+
+        assert!(stream_parser.push_line("--- a/list.txt\n").is_empty());
+        let sections = stream_parser.push_line("+++ b/list.txt\n");
+        assert_eq!(1, sections.len());
+        match &sections[0] {
+            SECTION::FILE(file) => assert_eq!(4, file.hunks[0].content.len()),
+            section => panic!("Error: expected a file, got {:?}", section),
+        }
+    }
+
+    #[test]
+    fn get_git_filenames_test() {
+        let inputs = vec![
+            ("a/script.sh b/script.sh", ("a/script.sh", "b/script.sh")),
+            ("a/a b/c.txt b/a b/c.txt", ("a/a b/c.txt", "b/a b/c.txt")),
+            (
+                "a/old.txt b/new b/file.txt",
+                ("a/old.txt", "b/new b/file.txt"),
+            ),
+            (
+                r#""a/my file \303\251.txt" "b/my file \303\251.txt""#,
+                ("a/my file é.txt", "b/my file é.txt"),
+            ),
+            (
+                r#"a/plain.txt "b/renamed \303\251.txt""#,
+                ("a/plain.txt", "b/renamed é.txt"),
+            ),
+            (
+                r#""a/tab\t\"quote\".txt" b/plain.txt"#,
+                ("a/tab\t\"quote\".txt", "b/plain.txt"),
+            ),
+        ];
+        for (input, (old, new)) in inputs {
+            assert_eq!(
+                Some((Cow::from(old), Cow::from(new))),
+                get_git_filenames(input, None, &[]),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(None, get_git_filenames("old.txt new.txt", None, &[]));
+        assert_eq!(
+            None,
+            get_git_filenames(r#""a/unclosed.txt b/unclosed.txt"#, None, &[])
+        );
+    }
+
+    #[test]
+    fn unquote_path_test() {
+        assert_eq!("plain.txt", unquote_path("plain.txt"));
+        assert_eq!(
+            "back\\slash \"é\"\n",
+            unquote_path(r#""back\\slash \"\303\251\"\n""#)
+        );
+        assert_eq!("\"", unquote_path("\""));
+    }
+
+    #[test]
+    fn parse_content_quoted_test() {
+        let result = parse_strict(include_str!("../test/resources/quoted.patch")).unwrap();
+        let filenames: Vec<(Option<&str>, &str)> = result
+            .iter()
+            .map(|file| (file.old_filename.as_deref(), file.filename.as_str()))
+            .collect();
+
+        assert_eq!(
+            vec![
+                (None, "a b/c.txt"),
+                (None, "my file é.txt"),
+                (Some("plain.txt"), "renamed é.txt"),
+                (None, "tab\t\"quote\".txt"),
+            ],
+            filenames
+        );
+    }
+
+    #[test]
+    fn get_git_prefixes_test() {
+        let inputs = vec![
+            (("a/src/main.rs", "b/src/main.rs"), (2, 2)),
+            (("src/main.rs", "src/main.rs"), (0, 0)),
+            (("old/main.rs", "new/main.rs"), (4, 4)),
+            (("i/main.rs", "w/main.rs"), (2, 2)),
+            (("a/old.txt", "b/new.txt"), (2, 2)),
+            (("old.txt", "new.txt"), (0, 0)),
+        ];
+        for ((old, new), expected) in inputs {
+            assert_eq!(expected, get_git_prefixes(old, new), "{} {}", old, new);
+        }
+    }
+
+    #[test]
+    fn parse_sections_with_options_prefixes_test() {
+        let input = "diff --git old-list.txt new-list.txt\nindex 1234567..89abcde 100644\n--- old-list.txt\n+++ new-list.txt\n@@ -1 +1 @@\n-apples\n+pears\n";
+        let get_filename = |sections: Vec<SECTION>| match &sections[..] {
+            [SECTION::FILE(file)] => file.filename.clone(),
+            sections => panic!("Error: expected a file, got {:?}", sections),
+        };
+
+        // the prefixes are no path components, so they can't be guessed
+        assert_eq!("new-list.txt", get_filename(parse_sections(input)));
+
+        let mut options = ParseOptions {
+            prefixes: Some(("old-".to_string(), "new-".to_string())),
+        };
+        assert_eq!(
+            "list.txt",
+            get_filename(parse_sections_with_options(input, &options))
+        );
+
+        // names without the prefixes of the options have guessed prefixes
+        options.prefixes = Some(("x/".to_string(), "y/".to_string()));
+        assert_eq!(
+            "new-list.txt",
+            get_filename(parse_sections_with_options(input, &options))
+        );
+    }
+
+    #[test]
+    fn parse_content_prefixes_test() {
+        let result = parse_strict(include_str!("../test/resources/prefixes.patch")).unwrap();
+        let files: Vec<(MODIFIER, &str)> = result
+            .iter()
+            .map(|file| (file.modifier, file.filename.as_str()))
+            .collect();
+
+        assert_eq!(
+            vec![
+                (MODIFIER::MODIFIED, "list.txt"),
+                (MODIFIER::ADD, "main.rs"),
+                (MODIFIER::MODIFIED, "list.txt"),
+                (MODIFIER::ADD, "main.rs"),
+            ],
+            files
+        );
+    }
+
+    #[test]
+    fn parse_content_dev_null_test() {
+        let input = "diff --git a/new.txt b/new.txt\n--- /dev/null\n+++ b/new.txt\n@@ -0,0 +1 @@\n+apples\ndiff --git a/old.txt b/old.txt\n--- a/old.txt\n+++ /dev/null\n@@ -1 +0,0 @@\n-pears\n";
+        let result = parse_strict(input).unwrap();
+
+        assert_eq!(MODIFIER::ADD, result[0].modifier);
+        assert_eq!("new.txt", result[0].filename);
+        assert_eq!(MODIFIER::DELETE, result[1].modifier);
+        assert_eq!("old.txt", result[1].filename);
+    }
+
+    #[test]
+    fn parse_strict_paths_mismatch_test() {
+        let input = "diff --git a/list.txt b/list.txt\n--- a/other.txt\n+++ b/other.txt\n@@ -1 +1 @@\n-apples\n+pears\n";
+        assert_eq!(
+            Err(ParseError::Header { line: 1, column: 1 }),
+            parse_strict(input)
+        );
+    }
 }
@@ -1,16 +1,23 @@
 //! For printing the diff content in a modern output style to the terminal,
 //! this modul prints the file(s) objects from the parser with code
-//! highlighting and a colourful diff
+//! highlighting and a colourful diff. The commits of `git log -p` get a
+//! banner above their files. Text which is no diff is printed unchanged.
+//!
+//! The output is written directly to a writer (`io::Write`), e.g. a locked
+//! stdout, a pager pipe or a `Vec<u8>`, without building it as a string.
 
-use ansi_term;
-use term_size;
+use ansi_term::{Colour, Style};
+use std::io::{self, Write};
 
-use self::ansi_term::{Colour, Style};
-use crate::file::{File, LINE, MODIFIER};
+use crate::file::{Binary, Commit, File, BINARY_SIZE, CHANGE, LINE, MODIFIER, SECTION};
+use crate::syntax::{Highlight, Highlighter};
 
 // file border colour
 const FIXED_COLOUR: u8 = 244;
 
+// fallback width if the output is not a terminal
+const DEFAULT_TERM_WIDTH: usize = 80;
+
 // char definitions
 // for border, modifier and the outline painting
 const LINE: char = '─';
@@ -22,211 +29,1246 @@ const LINE_CUT1: char = '⸝';
 const LINE_CUT2: char = '⸜';
 const LINE_CUT3: char = '⸍';
 const LINE_CUT4: char = '⸌';
+const LINE_TRUNCATED: char = '…';
+// border of the commit banner
+const COMMIT_LINE: char = '━';
+// indent of the commit fields and the message
+const COMMIT_INDENT: &str = " ";
+// marker of a line without newline at the end of the file
+const NO_NEWLINE: char = '⌀';
+// position of the section heading in the cut
+const CUT_HEADING_INDENT: usize = 4;
 const MODIFIER_ADD: char = 'A';
 const MODIFIER_MODIFIED: char = 'M';
+const MODIFIER_RENAMED: char = 'R';
+const MODIFIER_COPIED: char = 'C';
 const MODIFIER_DELETE: char = 'D';
 
+// background colours for the changed parts of paired lines
+const EMPHASIS_ADD_COLOUR: u8 = 22;
+const EMPHASIS_REM_COLOUR: u8 = 52;
+// background colours of syntax highlighted lines and their changed parts
+const SYNTAX_ADD_COLOUR: u8 = 22;
+const SYNTAX_REM_COLOUR: u8 = 52;
+const SYNTAX_EMPHASIS_ADD_COLOUR: u8 = 28;
+const SYNTAX_EMPHASIS_REM_COLOUR: u8 = 88;
+// limits for the word diff of paired lines, the similarity in percent
+const EMPHASIS_MAX_TOKENS: usize = 250_000;
+const EMPHASIS_MIN_SIMILARITY: usize = 30;
+
+// the indices of the lines in a row of the column view, `None` is an empty
+// filler
+type SplitRow = (Option<usize>, Option<usize>);
+// a part of a line content with its style
+type Segment = (Style, String);
+// the parts of a line content and if they are changed
+type Emphasis = Vec<(String, bool)>;
+
+/// The settings of the printer. New options can be added in a minor
+/// version, so start with the defaults and change the fields:
+///
+/// ```
+/// let mut options = diff_rs::PrintOptions::default();
+/// options.columnview = true;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct PrintOptions {
+    /// print removed and added lines side by side
+    pub columnview: bool,
+    /// highlight the line contents by the language of the file
+    pub syntax: bool,
+    /// the width of the output, the width of the terminal if it is `None`
+    pub width: Option<usize>,
+    /// paint the output with colours and styles, otherwise it's plain text,
+    /// e.g. for a log file
+    pub colour: bool,
+}
+
+impl Default for PrintOptions {
+    fn default() -> PrintOptions {
+        PrintOptions {
+            columnview: false,
+            syntax: true,
+            width: None,
+            colour: true,
+        }
+    }
+}
+
 /// Main print method for printing the file content and the styling
 ///
 /// # Arguments
 ///
-/// * `files` - files that will be printed
-///
-pub fn print(files: &Vec<File>, _columnview: Option<&str>) -> String {
-    let mut printable_output: String = String::new();
-    let terminal_size = term_size::dimensions();
-    let term_width = terminal_size.unwrap_or((0, 0)).0;
-
-    // for every file in the diff
-    files.iter().for_each(|file| {
-        // linenumber width
-        let max_line_number = file.get_max_line_number_size();
-        let ln_width = max_line_number.to_string().chars().count() + 3;
-
-        // filename
-        printable_output.push_str(&get_horizontal_line(&term_width, &ln_width, LINE_ANCHOR_UP));
-        printable_output.push_str(&get_filename(
-            &file.modifier,
-            &file.filename,
-            &file.commit_id,
-            &ln_width,
-        ));
-        printable_output.push_str(&get_horizontal_line(
-            &term_width,
+/// * `output` - the writer for the printed sections
+/// * `sections` - files, commits and text that will be printed
+/// * `options` - the settings of the output
+///
+pub fn print(
+    output: &mut impl Write,
+    sections: &[SECTION],
+    options: &PrintOptions,
+) -> io::Result<()> {
+    let printer = Printer::new(options);
+    sections
+        .iter()
+        .try_for_each(|section| printer.print_section(output, section))
+}
+
+/// Prints the sections one by one with the same settings, e.g. the sections
+/// of a stream while it is read
+pub struct Printer {
+    term_width: usize,
+    columnview: bool,
+    colour: bool,
+    highlighter: Option<Highlighter>,
+}
+
+impl Printer {
+    /// Creates the printer, the syntax definitions are only loaded once
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - the settings of the output
+    ///
+    pub fn new(options: &PrintOptions) -> Printer {
+        Printer {
+            term_width: options.width.unwrap_or_else(get_term_width),
+            columnview: options.columnview,
+            colour: options.colour,
+            highlighter: if options.syntax {
+                Some(Highlighter::new())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Writes a file, a commit with the banner and its files or the
+    /// unchanged text
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - the writer for the printed section
+    /// * `section` - the section that will be printed
+    ///
+    pub fn print_section(&self, output: &mut impl Write, section: &SECTION) -> io::Result<()> {
+        if self.colour {
+            self.write_section(output, section)
+        } else {
+            self.write_section(&mut PlainWriter::new(output), section)
+        }
+    }
+
+    fn write_section(&self, output: &mut impl Write, section: &SECTION) -> io::Result<()> {
+        match section {
+            SECTION::FILE(file) => self.print_file(output, file),
+            SECTION::COMMIT(commit) => {
+                write_commit(output, commit, &self.term_width)?;
+                commit
+                    .files
+                    .iter()
+                    .try_for_each(|file| self.print_file(output, file))
+            }
+            SECTION::TEXT(text) => output.write_all(text.as_bytes()),
+        }
+    }
+
+    fn print_file(&self, output: &mut impl Write, file: &File) -> io::Result<()> {
+        write_file(
+            output,
+            file,
+            &self.term_width,
+            self.columnview,
+            self.highlighter.as_ref(),
+        )
+    }
+}
+
+/// Removes the escape sequences of the colours and styles from the output
+struct PlainWriter<W: Write> {
+    output: W,
+    /// if the last written byte is part of an escape sequence
+    escape: bool,
+}
+
+impl<W: Write> PlainWriter<W> {
+    fn new(output: W) -> PlainWriter<W> {
+        PlainWriter {
+            output,
+            escape: false,
+        }
+    }
+}
+
+impl<W: Write> Write for PlainWriter<W> {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let mut start = 0;
+        for (i, byte) in buffer.iter().enumerate() {
+            if self.escape {
+                // the final byte of a sequence, e.g. the `m` of `ESC[1;32m`
+                if (0x40..=0x7e).contains(byte) && *byte != b'[' {
+                    self.escape = false;
+                    start = i + 1;
+                }
+            } else if *byte == 0x1b {
+                self.output.write_all(&buffer[start..i])?;
+                self.escape = true;
+            }
+        }
+        if !self.escape {
+            self.output.write_all(&buffer[start..])?;
+        }
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+/// Writes the banner of a commit with the commit id, the author, the date
+/// and the message between two thick lines. A patch of a series has its
+/// number in the series after the commit id.
+///
+/// # Arguments
+///
+/// * `output` - the writer for the banner
+/// * `commit` - the commit of `git log -p` or `git show`
+/// * `term_width` - the width of the terminal
+///
+fn write_commit(output: &mut impl Write, commit: &Commit, term_width: &usize) -> io::Result<()> {
+    let write_border = |output: &mut _| {
+        write_chars(
+            output,
+            Colour::Yellow.normal(),
+            term_width.saturating_sub(1),
+            |_| COMMIT_LINE,
+            "\n",
+        )
+    };
+    let field_style = Colour::Fixed(FIXED_COLOUR);
+
+    write_border(output)?;
+    write!(
+        output,
+        "{}{}",
+        COMMIT_INDENT,
+        Colour::Yellow.bold().paint(&commit.id)
+    )?;
+    if !commit.refs.is_empty() {
+        write!(output, " {}", Colour::Cyan.bold().paint(&commit.refs))?;
+    }
+    if let Some((number, count)) = commit.series {
+        write!(
+            output,
+            " {}",
+            Colour::Purple
+                .bold()
+                .paint(format!("[PATCH {}/{}]", number, count))
+        )?;
+    }
+    writeln!(output)?;
+    for (name, value) in [("Author", &commit.author), ("Date", &commit.date)] {
+        if !value.is_empty() {
+            writeln!(
+                output,
+                "{}{}{}:{} {}",
+                COMMIT_INDENT,
+                field_style.prefix(),
+                name,
+                field_style.suffix(),
+                value
+            )?;
+        }
+    }
+    if !commit.message.is_empty() {
+        writeln!(output)?;
+        // the subject is the first line of the message
+        for (i, line) in commit.message.lines().enumerate() {
+            if line.is_empty() {
+                writeln!(output)?;
+            } else if i == 0 {
+                writeln!(
+                    output,
+                    "{}{}",
+                    COMMIT_INDENT,
+                    Style::new().bold().paint(line)
+                )?;
+            } else {
+                writeln!(output, "{}{}", COMMIT_INDENT, line)?;
+            }
+        }
+    }
+    write_border(output)
+}
+
+/// Writes a file with its header, the hunks and the borders
+///
+/// # Arguments
+///
+/// * `output` - the writer for the file
+/// * `file` - the file that will be printed
+/// * `term_width` - the width of the terminal
+/// * `columnview` - print removed and added lines side by side
+/// * `highlighter` - highlights the line contents, if syntax highlighting is enabled
+///
+fn write_file(
+    output: &mut impl Write,
+    file: &File,
+    term_width: &usize,
+    columnview: bool,
+    highlighter: Option<&Highlighter>,
+) -> io::Result<()> {
+    // files without hunks and combined diffs of merges have no columns
+    let columnview = columnview && !file.hunks.is_empty() && !file.is_combined();
+
+    // linenumber width
+    let max_line_number = file.get_max_line_number_size();
+    let ln_width = max_line_number.to_string().chars().count() + 3;
+
+    // filename
+    write_horizontal_line(output, term_width, &ln_width, LINE_ANCHOR_UP)?;
+    write_filename(output, file, &ln_width)?;
+    if columnview {
+        write_horizontal_line_split(
+            output,
+            term_width,
             &ln_width,
             LINE_ANCHOR_MIDDLE,
-        ));
+            LINE_ANCHOR_UP,
+        )?;
+    } else {
+        write_horizontal_line(output, term_width, &ln_width, LINE_ANCHOR_MIDDLE)?;
+    }
+
+    // binary or files without hunks
+    if let Some(binary) = &file.binary {
+        write_binary(output, &ln_width, binary)?;
+    } else if file.hunks.is_empty() {
+        write_no_content(output, &ln_width, file)?;
+    }
+
+    // the section heading of the first hunk, the others are in the cuts
+    if let Some(hunk) = file.hunks.first() {
+        write_heading(output, &ln_width, &hunk.heading)?;
+    }
 
-        // hunks
-        for i in 0..file.hunks.len() {
-            for line in &file.hunks[i].content {
-                printable_output.push_str(&get_line_content(&ln_width, &line));
+    // hunks
+    for i in 0..file.hunks.len() {
+        let lines = &file.hunks[i].content;
+        let emphasis = get_hunk_emphasis(lines);
+        let highlight = match highlighter {
+            Some(highlighter) => highlighter.highlight_hunk(&file.filename, lines),
+            None => vec![None; lines.len()],
+        };
+        if columnview {
+            for row in get_split_rows(lines) {
+                write_line_content_split(
+                    output, term_width, &ln_width, lines, &emphasis, &highlight, row,
+                )?;
             }
-            if file.hunks.len() > 1 && file.hunks.len() - 1 != i {
-                printable_output.push_str(&get_cut(&term_width));
+        } else {
+            for (i, line) in lines.iter().enumerate() {
+                write_line_content(
+                    output,
+                    &ln_width,
+                    line,
+                    emphasis[i].as_ref(),
+                    highlight[i].as_ref(),
+                )?;
             }
         }
+        if file.hunks.len() > 1 && file.hunks.len() - 1 != i {
+            write_cut(output, term_width, &file.hunks[i + 1].heading)?;
+        }
+    }
 
-        printable_output.push_str(&get_horizontal_line(
-            &term_width,
+    if columnview {
+        write_horizontal_line_split(
+            output,
+            term_width,
             &ln_width,
             LINE_ANCHOR_DOWN,
-        ));
-    });
+            LINE_ANCHOR_DOWN,
+        )
+    } else {
+        write_horizontal_line(output, term_width, &ln_width, LINE_ANCHOR_DOWN)
+    }
+}
 
-    printable_output
+/// Returns the width of the terminal or a default width, if the output is
+/// not a terminal
+fn get_term_width() -> usize {
+    term_size::dimensions()
+        .map(|(width, _)| width)
+        .unwrap_or(DEFAULT_TERM_WIDTH)
 }
 
-/// Returns a horizontal line at the beginning, after the filename and at the
+/// Writes a horizontal line at the beginning, after the filename and at the
 /// end of a file.
 ///
 /// # Arguments
 ///
+/// * `output` - the writer for the line
 /// * `width` - the terminal width for line length
 /// * `ln_width` - the width of the linenumbers column
 /// * `indent_char` - the char to print at the indent for the vertical column
-/// line
+///   line
 ///
-fn get_horizontal_line(width: &usize, ln_width: &usize, indent_char: char) -> String {
-    let mut line = String::new();
-    for i in 1..*width {
-        if i == *ln_width {
-            line.push(indent_char);
-        }
-        line.push(LINE);
-    }
-    line.push_str("\n");
-    Colour::Fixed(FIXED_COLOUR).paint(line).to_string()
+fn write_horizontal_line(
+    output: &mut impl Write,
+    width: &usize,
+    ln_width: &usize,
+    indent_char: char,
+) -> io::Result<()> {
+    // the indent char is an additional char before the line char
+    let count = width.saturating_sub(1) + usize::from(*ln_width < *width);
+    write_chars(
+        output,
+        Colour::Fixed(FIXED_COLOUR).normal(),
+        count,
+        |i| {
+            if i + 1 == *ln_width {
+                indent_char
+            } else {
+                LINE
+            }
+        },
+        "\n",
+    )
 }
 
-/// Returns a outline after every hunk in a file to show the cut in a file.
+/// Writes a horizontal line for the column view with the indents of both
+/// linenumber columns and the separator between the columns.
 ///
 /// # Arguments
 ///
+/// * `output` - the writer for the line
 /// * `width` - the terminal width for line length
+/// * `ln_width` - the width of the linenumbers column
+/// * `indent_char` - the char to print at the indent of the left linenumber
+///   column
+/// * `split_char` - the char to print at the column separator and the indent
+///   of the right linenumber column
 ///
-fn get_cut(width: &usize) -> String {
-    let mut output = String::new();
-    // down cut
-    for _ in (1..*width).step_by(2) {
-        output.push_str(&format!(
-            "{}",
-            Colour::Fixed(FIXED_COLOUR).paint(LINE_CUT1.to_string())
-        ));
-        output.push_str(&format!(
+fn write_horizontal_line_split(
+    output: &mut impl Write,
+    width: &usize,
+    ln_width: &usize,
+    indent_char: char,
+    split_char: char,
+) -> io::Result<()> {
+    let half = width / 2;
+    write_chars(
+        output,
+        Colour::Fixed(FIXED_COLOUR).normal(),
+        *width,
+        |i| {
+            let i = i + 1;
+            if i == *ln_width {
+                indent_char
+            } else if i == half || i == half + ln_width {
+                split_char
+            } else {
+                LINE
+            }
+        },
+        "\n",
+    )
+}
+
+/// Writes a outline after every hunk in a file to show the cut in a file.
+/// The section heading of the next hunk is shown in the cut.
+///
+/// # Arguments
+///
+/// * `output` - the writer for the cut
+/// * `width` - the terminal width for line length
+/// * `heading` - the section heading of the next hunk
+///
+// `is_multiple_of` needs Rust 1.87
+#[allow(clippy::manual_is_multiple_of)]
+fn write_cut(output: &mut impl Write, width: &usize, heading: &str) -> io::Result<()> {
+    let cut_width = (1..*width).step_by(2).count() * 2;
+    let style = Colour::Fixed(FIXED_COLOUR).normal();
+    let down_cut = |i: usize| {
+        if i % 2 == 0 {
+            LINE_CUT1
+        } else {
+            LINE_CUT2
+        }
+    };
+
+    // down cut with the heading
+    if heading.is_empty() || cut_width <= CUT_HEADING_INDENT + 2 {
+        write_chars(output, style, cut_width, down_cut, "")?;
+    } else {
+        let heading: String = format!(" {} ", heading)
+            .chars()
+            .take(cut_width - CUT_HEADING_INDENT)
+            .collect();
+        let heading_width = heading.chars().count();
+        write_chars(output, style, CUT_HEADING_INDENT, down_cut, "")?;
+        write!(
+            output,
             "{}",
-            Colour::Fixed(FIXED_COLOUR).paint(LINE_CUT2.to_string())
-        ));
+            Colour::Fixed(FIXED_COLOUR).italic().paint(heading)
+        )?;
+        write_chars(
+            output,
+            style,
+            cut_width - CUT_HEADING_INDENT - heading_width,
+            down_cut,
+            "",
+        )?;
     }
-    output.push_str("\n");
+    writeln!(output)?;
 
     // up cut
-    for _ in (1..*width).step_by(2) {
-        output.push_str(&format!(
-            "{}",
-            Colour::Fixed(FIXED_COLOUR).paint(LINE_CUT3.to_string())
-        ));
-        output.push_str(&format!(
-            "{}",
-            Colour::Fixed(FIXED_COLOUR).paint(LINE_CUT4.to_string())
-        ));
+    write_chars(
+        output,
+        style,
+        cut_width,
+        |i| {
+            if i % 2 == 0 {
+                LINE_CUT3
+            } else {
+                LINE_CUT4
+            }
+        },
+        "",
+    )?;
+    writeln!(output)
+}
+
+/// Writes a run of chars with one style, e.g. a border line
+///
+/// # Arguments
+///
+/// * `output` - the writer for the chars
+/// * `style` - the style of all chars
+/// * `count` - the number of chars
+/// * `get_char` - returns the char at a position
+/// * `end` - the text after the chars with the same style, e.g. the newline
+///   of a border line
+///
+fn write_chars(
+    output: &mut impl Write,
+    style: Style,
+    count: usize,
+    get_char: impl Fn(usize) -> char,
+    end: &str,
+) -> io::Result<()> {
+    write!(output, "{}", style.prefix())?;
+    let mut buffer = [0; 4];
+    for i in 0..count {
+        output.write_all(get_char(i).encode_utf8(&mut buffer).as_bytes())?;
     }
-    output.push_str("\n");
-    output
+    output.write_all(end.as_bytes())?;
+    write!(output, "{}", style.suffix())
 }
 
-/// Returns the filename in the header row of a file
+/// Writes the filename in the header row of a file, for renamed and copied
+/// files with the old filename and the similarity and the modes of a mode
+/// change
 ///
 /// # Arguments
 ///
-/// * `modifier` - the git modifier (add, delete, ...)
-/// * `filename` - the filename
-/// * `commit_id` - commit id of the file
+/// * `output` - the writer for the header row
+/// * `file` - the file with the git modifier (add, delete, ...), filenames
+///   and commit id
 /// * `ln_width` - linenumber column width for indent
 ///
-fn get_filename(modifier: &MODIFIER, filename: &str, commit_id: &str, ln_width: &usize) -> String {
-    let mut output = String::new();
-    let modifier_symbol = match modifier {
-        MODIFIER::ADD => Colour::Green.bold().paint(MODIFIER_ADD.to_string()),
-        MODIFIER::MODIFIED => Colour::Yellow.bold().paint(MODIFIER_MODIFIED.to_string()),
-        MODIFIER::RENAMED => Colour::Purple.bold().paint(MODIFIER_MODIFIED.to_string()),
-        MODIFIER::DELETE => Colour::Red.bold().paint(MODIFIER_DELETE.to_string()),
+fn write_filename(output: &mut impl Write, file: &File, ln_width: &usize) -> io::Result<()> {
+    let (modifier_symbol, modifier_colour) = match file.modifier {
+        MODIFIER::ADD => (MODIFIER_ADD, Colour::Green),
+        MODIFIER::MODIFIED => (MODIFIER_MODIFIED, Colour::Yellow),
+        MODIFIER::RENAMED => (MODIFIER_RENAMED, Colour::Purple),
+        MODIFIER::COPIED => (MODIFIER_COPIED, Colour::Cyan),
+        MODIFIER::DELETE => (MODIFIER_DELETE, Colour::Red),
     };
+    let fixed_style = Colour::Fixed(FIXED_COLOUR).normal();
+    let filename_style = Style::new().bold();
 
-    for _ in 1..*ln_width {
-        output.push_str(" ");
-    }
-    output.push_str(&format!(
-        "{} {} {} {}{}\n",
-        Colour::Fixed(FIXED_COLOUR).paint("│"),
-        modifier_symbol,
-        Style::new().bold().paint(filename),
-        Colour::Blue.bold().paint("@"),
-        Colour::Blue.paint(commit_id),
-    ));
-
-    output
-}
-
-fn get_line_number(ln_width: &usize, line_number: &usize) -> String {
-    let mut output = String::new();
-    for i in 1..*ln_width {
-        if i + line_number.to_string().chars().count() + 1 == *ln_width {
-            output.push_str(&format!(
-                "{} ",
-                Colour::Fixed(FIXED_COLOUR).paint(line_number.to_string())
-            ));
-            break;
-        } else {
-            output.push_str(" ");
-        }
+    write!(
+        output,
+        "{:indent$}{} {} {}",
+        "",
+        fixed_style.paint("│"),
+        modifier_colour.bold().paint(modifier_symbol.to_string()),
+        filename_style.prefix(),
+        indent = ln_width.saturating_sub(1)
+    )?;
+    if let Some(old_filename) = &file.old_filename {
+        write!(output, "{} → ", old_filename)?;
     }
-    output.push_str(&format!(
-        "{}",
-        Colour::Fixed(FIXED_COLOUR).paint(LINENUMBER_SEPERATOR.to_string())
-    ));
+    write!(output, "{}{}", file.filename, filename_style.suffix())?;
+    if let Some(similarity) = file.similarity {
+        write!(
+            output,
+            "{} ({}%){}",
+            fixed_style.prefix(),
+            similarity,
+            fixed_style.suffix()
+        )?;
+    }
+    if let (Some(old_mode), Some(new_mode)) = (&file.old_mode, &file.new_mode) {
+        write!(
+            output,
+            "{} {} → {}{}",
+            fixed_style.prefix(),
+            old_mode,
+            new_mode,
+            fixed_style.suffix()
+        )?;
+    }
+    if !file.commit_id.is_empty() {
+        write!(
+            output,
+            " {}{}",
+            Colour::Blue.bold().paint("@"),
+            Colour::Blue.paint(&file.commit_id),
+        )?;
+    }
+    writeln!(output)
+}
 
-    output
+/// Writes the linenumber column with the number right aligned. A number which
+/// is too long for the column is left out.
+///
+/// # Arguments
+///
+/// * `output` - the writer for the linenumber column
+/// * `ln_width` - linenumber column width for indent
+/// * `line_number` - the number of the line
+///
+fn write_line_number(
+    output: &mut impl Write,
+    ln_width: &usize,
+    line_number: &usize,
+) -> io::Result<()> {
+    let fixed_style = Colour::Fixed(FIXED_COLOUR).normal();
+    let digits = line_number.to_string().chars().count();
+    if digits + 2 <= *ln_width {
+        write!(
+            output,
+            "{:indent$}{}{}{} ",
+            "",
+            fixed_style.prefix(),
+            line_number,
+            fixed_style.suffix(),
+            indent = ln_width - digits - 2
+        )?;
+    } else {
+        write!(
+            output,
+            "{:indent$}",
+            "",
+            indent = ln_width.saturating_sub(1)
+        )?;
+    }
+    write_chars(output, fixed_style, 1, |_| LINENUMBER_SEPERATOR, "")
+}
+
+/// Writes the linenumber column without a number for the empty filler
+/// lines in the column view
+///
+/// # Arguments
+///
+/// * `output` - the writer for the linenumber column
+/// * `ln_width` - linenumber column width for indent
+///
+fn write_empty_line_number(output: &mut impl Write, ln_width: &usize) -> io::Result<()> {
+    write!(
+        output,
+        "{:indent$}",
+        "",
+        indent = ln_width.saturating_sub(1)
+    )?;
+    write_chars(
+        output,
+        Colour::Fixed(FIXED_COLOUR).normal(),
+        1,
+        |_| LINENUMBER_SEPERATOR,
+        "",
+    )
 }
 
-/// Returns the line content with the different colours for the diff
+/// Writes the body line of a changed binary file with the sizes of both
+/// sides, if they are known
 ///
 /// # Arguments
 ///
+/// * `output` - the writer for the body line
+/// * `ln_width` - linenumber column width for indent
+/// * `binary` - the sizes of the binary file
+///
+fn write_binary(output: &mut impl Write, ln_width: &usize, binary: &Binary) -> io::Result<()> {
+    let sizes = match (&binary.old_size, &binary.new_size) {
+        (None, None) => String::new(),
+        (old_size, new_size) => format!(
+            " ({} → {})",
+            old_size
+                .as_ref()
+                .map_or("?".to_string(), format_binary_size),
+            new_size
+                .as_ref()
+                .map_or("?".to_string(), format_binary_size),
+        ),
+    };
+    write_body_line(output, ln_width, &format!("binary changed{}", sizes))
+}
+
+/// Writes the body line of a file without hunks and binary content, which
+/// explains why there is no content
+///
+/// # Arguments
+///
+/// * `output` - the writer for the body line
+/// * `ln_width` - linenumber column width for indent
+/// * `file` - the file without hunks
+///
+fn write_no_content(output: &mut impl Write, ln_width: &usize, file: &File) -> io::Result<()> {
+    let text = match file.modifier {
+        MODIFIER::ADD => "empty file added",
+        MODIFIER::DELETE => "empty file deleted",
+        MODIFIER::RENAMED => "renamed without changes",
+        MODIFIER::COPIED => "copied without changes",
+        MODIFIER::MODIFIED if file.old_mode.is_some() => "only the file mode changed",
+        MODIFIER::MODIFIED => "no content changes",
+    };
+    write_body_line(output, ln_width, text)
+}
+
+/// Writes the section heading of the first hunk in a line above it, like the
+/// headings of the next hunks in the cuts. An empty heading is left out.
+///
+/// # Arguments
+///
+/// * `output` - the writer for the heading line
+/// * `ln_width` - linenumber column width for indent
+/// * `heading` - the section heading of the hunk, e.g. the function
+///
+fn write_heading(output: &mut impl Write, ln_width: &usize, heading: &str) -> io::Result<()> {
+    if heading.is_empty() {
+        return Ok(());
+    }
+    write_empty_line_number(output, ln_width)?;
+    writeln!(
+        output,
+        " {}",
+        Colour::Fixed(FIXED_COLOUR).italic().paint(heading)
+    )
+}
+
+/// Writes a line with an explanation instead of the line contents
+///
+/// # Arguments
+///
+/// * `output` - the writer for the body line
+/// * `ln_width` - linenumber column width for indent
+/// * `text` - the explanation
+///
+fn write_body_line(output: &mut impl Write, ln_width: &usize, text: &str) -> io::Result<()> {
+    let style = Style::new().italic();
+    write_empty_line_number(output, ln_width)?;
+    writeln!(output, "{} {}{}", style.prefix(), text, style.suffix())
+}
+
+/// Returns the size of a binary file in a human readable unit, a delta size
+/// is marked with a `Δ`
+fn format_binary_size(size: &BINARY_SIZE) -> String {
+    let (prefix, bytes) = match size {
+        BINARY_SIZE::LITERAL(bytes) => ("", *bytes),
+        BINARY_SIZE::DELTA(bytes) => ("Δ", *bytes),
+    };
+    let units = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{}{} B", prefix, bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{}{:.1} {}", prefix, value, units[unit])
+}
+
+/// Writes the line content with the different colours for the diff
+///
+/// # Arguments
+///
+/// * `output` - the writer for the line
 /// * `ln_width` - linenumber column width for indent
 /// * `line` - the line object with their modifiers and content
+/// * `emphasis` - the changed parts of the line, if it is paired with another
+///   line
+/// * `highlight` - the syntax highlighting of the line
 ///
-fn get_line_content(ln_width: &usize, line: &LINE) -> String {
-    let mut output = String::new();
+fn write_line_content(
+    output: &mut impl Write,
+    ln_width: &usize,
+    line: &LINE,
+    emphasis: Option<&Emphasis>,
+    highlight: Option<&Highlight>,
+) -> io::Result<()> {
     match line {
-        LINE::ADD { number, line } => {
-            output.push_str(&get_line_number(&ln_width, &number));
-            output.push_str(&format!(
-                "{}\n",
-                Colour::Green.paint(format!("+{}", line.to_string()))
-            ))
+        LINE::ADD { number, .. } | LINE::REM { number, .. } | LINE::COMBINED { number, .. } => {
+            write_line_number(output, ln_width, number)?
         }
-        LINE::REM { number, line } => {
-            output.push_str(&get_line_number(&ln_width, &number));
-            output.push_str(&format!(
-                "{}\n",
-                Colour::Red.paint(format!("-{}", line.to_string()))
-            ))
+        LINE::NOP { number_right, .. } => write_line_number(output, ln_width, number_right)?,
+    }
+    write_segments(output, &get_line_segments(line, emphasis, highlight), None)?;
+    writeln!(output)
+}
+
+/// Returns the styled parts of a line with the diff prefix. Without an
+/// emphasis the whole line is coloured, otherwise the changed parts get a
+/// stronger background. With a syntax highlighting the line content gets the
+/// colours of the syntax and changed lines a background instead. A line
+/// without newline at the end of the file gets a marker. A line of a combined
+/// diff has a prefix for every parent.
+///
+/// # Arguments
+///
+/// * `line` - the line object with their modifiers and content
+/// * `emphasis` - the changed parts of the line
+/// * `highlight` - the syntax highlighting of the line
+///
+fn get_line_segments(
+    line: &LINE,
+    emphasis: Option<&Emphasis>,
+    highlight: Option<&Highlight>,
+) -> Vec<Segment> {
+    let (content, parents) = match line {
+        LINE::ADD { line, .. } | LINE::REM { line, .. } | LINE::NOP { line, .. } => (line, None),
+        LINE::COMBINED { line, parents, .. } => (line, Some(parents)),
+    };
+    let (style, emphasis_style) = get_change_styles(line.get_change());
+
+    let mut segments: Vec<Segment> = Vec::new();
+    match parents {
+        Some(parents) => parents.iter().for_each(|change| {
+            let (prefix, _) = get_change_prefix(*change);
+            push_segment(&mut segments, get_change_styles(*change).0, prefix)
+        }),
+        None => segments.push((style, get_change_prefix(line.get_change()).0.to_string())),
+    }
+    match (emphasis, highlight) {
+        (_, Some(highlight)) => {
+            let changed: Vec<bool> = match emphasis {
+                Some(parts) => parts
+                    .iter()
+                    .flat_map(|(part, changed)| part.chars().map(move |_| *changed))
+                    .collect(),
+                None => Vec::new(),
+            };
+            let (background, emphasis_background) = get_change_prefix(line.get_change()).1;
+            let mut i = 0;
+            for (colour, part) in highlight {
+                for c in part.chars() {
+                    let mut char_style = colour.normal();
+                    char_style.background = if changed.get(i).copied().unwrap_or(false) {
+                        emphasis_background
+                    } else {
+                        background
+                    };
+                    push_segment(&mut segments, char_style, c);
+                    i += 1;
+                }
+            }
         }
-        LINE::NOP {
-            number_left: _,
-            number_right,
-            line,
-        } => {
-            output.push_str(&get_line_number(&ln_width, &number_right));
-            output.push_str(&format!(
-                "{}\n",
-                Colour::White.paint(format!(" {}", line.to_string()))
+        (Some(parts), None) => parts.iter().for_each(|(part, changed)| {
+            segments.push((
+                if *changed { emphasis_style } else { style },
+                part.to_string(),
             ))
+        }),
+        (None, None) => segments.push((style, content.to_string())),
+    }
+    match line {
+        LINE::ADD { no_newline, .. }
+        | LINE::REM { no_newline, .. }
+        | LINE::NOP { no_newline, .. }
+        | LINE::COMBINED { no_newline, .. } => {
+            if *no_newline {
+                push_segment(
+                    &mut segments,
+                    Colour::Fixed(FIXED_COLOUR).normal(),
+                    NO_NEWLINE,
+                );
+            }
         }
     }
 
-    output
+    segments
+}
+
+/// Returns the colour and the colour of the changed parts for a change
+fn get_change_styles(change: CHANGE) -> (Style, Style) {
+    match change {
+        CHANGE::ADD => (
+            Colour::Green.normal(),
+            Colour::Green.on(Colour::Fixed(EMPHASIS_ADD_COLOUR)),
+        ),
+        CHANGE::REM => (
+            Colour::Red.normal(),
+            Colour::Red.on(Colour::Fixed(EMPHASIS_REM_COLOUR)),
+        ),
+        CHANGE::NOP => (Colour::White.normal(), Colour::White.normal()),
+    }
+}
+
+/// Returns the diff prefix for a change and the backgrounds of a syntax
+/// highlighted line and its changed parts
+fn get_change_prefix(change: CHANGE) -> (char, (Option<Colour>, Option<Colour>)) {
+    match change {
+        CHANGE::ADD => (
+            '+',
+            (
+                Some(Colour::Fixed(SYNTAX_ADD_COLOUR)),
+                Some(Colour::Fixed(SYNTAX_EMPHASIS_ADD_COLOUR)),
+            ),
+        ),
+        CHANGE::REM => (
+            '-',
+            (
+                Some(Colour::Fixed(SYNTAX_REM_COLOUR)),
+                Some(Colour::Fixed(SYNTAX_EMPHASIS_REM_COLOUR)),
+            ),
+        ),
+        CHANGE::NOP => (' ', (None, None)),
+    }
+}
+
+/// Adds a char to the styled parts of a line, to the last part if it has the
+/// same style
+fn push_segment(segments: &mut Vec<Segment>, style: Style, c: char) {
+    match segments.last_mut() {
+        Some((last_style, text)) if *last_style == style => text.push(c),
+        _ => segments.push((style, c.to_string())),
+    }
+}
+
+/// Writes the styled parts of a line. Neighbouring parts with the same style
+/// are painted together.
+///
+/// # Arguments
+///
+/// * `output` - the writer for the line
+/// * `segments` - the styled parts of the line
+/// * `width` - if given, the line is truncated or filled with spaces to this
+///   width and tabs are expanded to keep the columns aligned
+///
+fn write_segments(
+    output: &mut impl Write,
+    segments: &[Segment],
+    width: Option<&usize>,
+) -> io::Result<()> {
+    let segments: Vec<Segment> = match width {
+        Some(_) => segments
+            .iter()
+            .map(|(style, text)| (*style, text.replace('\t', "    ")))
+            .collect(),
+        None => segments.to_vec(),
+    };
+    let count: usize = segments.iter().map(|(_, text)| text.chars().count()).sum();
+    let mut limit = match width {
+        Some(width) if count > *width => width.saturating_sub(1),
+        _ => count,
+    };
+
+    let mut fitted: Vec<Segment> = Vec::new();
+    for (style, text) in segments {
+        let text: String = text.chars().take(limit).collect();
+        limit -= text.chars().count();
+        match fitted.last_mut() {
+            Some((last_style, last_text)) if *last_style == style => last_text.push_str(&text),
+            _ => fitted.push((style, text)),
+        }
+    }
+    if let (Some(width), Some((_, last_text))) = (width, fitted.last_mut()) {
+        if count > *width {
+            if *width > 0 {
+                last_text.push(LINE_TRUNCATED);
+            }
+        } else {
+            last_text.push_str(&" ".repeat(width - count));
+        }
+    }
+
+    fitted
+        .iter()
+        .try_for_each(|(style, text)| write!(output, "{}", style.paint(text.as_str())))
+}
+
+/// Returns the changed parts of every paired removed and added line in a
+/// hunk, index by the position of the line in the hunk.
+///
+/// # Arguments
+///
+/// * `lines` - the lines of a hunk
+///
+fn get_hunk_emphasis(lines: &[LINE]) -> Vec<Option<Emphasis>> {
+    let mut emphasis: Vec<Option<Emphasis>> = vec![None; lines.len()];
+    for row in get_split_rows(lines) {
+        if let (Some(left), Some(right)) = row {
+            if let (LINE::REM { line: removed, .. }, LINE::ADD { line: added, .. }) =
+                (&lines[left], &lines[right])
+            {
+                if let Some((removed, added)) = get_emphasis(removed, added) {
+                    emphasis[left] = Some(removed);
+                    emphasis[right] = Some(added);
+                }
+            }
+        }
+    }
+
+    emphasis
+}
+
+/// Returns the changed parts of a removed and an added line, computed by a
+/// diff over the words of both lines. `None` if the lines are too long or too
+/// different for a useful highlighting.
+///
+/// # Arguments
+///
+/// * `removed` - the content of the removed line
+/// * `added` - the content of the added line
+///
+fn get_emphasis(removed: &str, added: &str) -> Option<(Emphasis, Emphasis)> {
+    let left = tokenize(removed);
+    let right = tokenize(added);
+    if left.len() * right.len() > EMPHASIS_MAX_TOKENS {
+        return None;
+    }
+
+    // longest common subsequence of the tokens
+    let mut table = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            table[i][j] = if left[i] == right[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut left_changed = vec![true; left.len()];
+    let mut right_changed = vec![true; right.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if left[i] == right[j] {
+            left_changed[i] = false;
+            right_changed[j] = false;
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    let common: usize = left
+        .iter()
+        .zip(&left_changed)
+        .filter(|(_, changed)| !**changed)
+        .map(|(token, _)| token.chars().count())
+        .sum();
+    let longest = removed.chars().count().max(added.chars().count());
+    if longest == 0 || common * 100 < longest * EMPHASIS_MIN_SIMILARITY {
+        return None;
+    }
+
+    Some((
+        join_tokens(&left, &left_changed),
+        join_tokens(&right, &right_changed),
+    ))
+}
+
+/// Splits a line into words, whitespace and single punctuation chars
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens: Vec<&str> = Vec::new();
+    let mut start = 0;
+    let mut last_kind = None;
+    for (i, c) in line.char_indices() {
+        let kind = if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        };
+        if i > start && (kind == 2 || last_kind != Some(kind)) {
+            tokens.push(&line[start..i]);
+            start = i;
+        }
+        last_kind = Some(kind);
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+
+    tokens
+}
+
+/// Joins neighbouring tokens with the same change state to parts
+fn join_tokens(tokens: &[&str], changed: &[bool]) -> Emphasis {
+    let mut parts: Emphasis = Vec::new();
+    for (token, changed) in tokens.iter().zip(changed) {
+        match parts.last_mut() {
+            Some((part, last_changed)) if last_changed == changed => part.push_str(token),
+            _ => parts.push((token.to_string(), *changed)),
+        }
+    }
+
+    parts
+}
+
+/// Returns the rows of a hunk for the column view as the indices of the
+/// lines. Unchanged lines are on both sides, removed lines on the left and
+/// added lines on the right. A run of removed lines followed by a run of
+/// added lines are paired row by row.
+///
+/// # Arguments
+///
+/// * `lines` - the lines of a hunk
+///
+fn get_split_rows(lines: &[LINE]) -> Vec<SplitRow> {
+    let mut rows: Vec<SplitRow> = Vec::new();
+    let mut removed: Vec<usize> = Vec::new();
+    let mut added: Vec<usize> = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        match line {
+            LINE::REM { .. } => {
+                if !added.is_empty() {
+                    push_split_rows(&mut rows, &mut removed, &mut added);
+                }
+                removed.push(i);
+            }
+            LINE::ADD { .. } => added.push(i),
+            // the lines of a combined diff are no pairs
+            LINE::NOP { .. } | LINE::COMBINED { .. } => {
+                push_split_rows(&mut rows, &mut removed, &mut added);
+                rows.push((Some(i), Some(i)));
+            }
+        }
+    }
+    push_split_rows(&mut rows, &mut removed, &mut added);
+
+    rows
+}
+
+/// Pairs the collected removed and added lines to rows and clears them
+fn push_split_rows(rows: &mut Vec<SplitRow>, removed: &mut Vec<usize>, added: &mut Vec<usize>) {
+    for i in 0..removed.len().max(added.len()) {
+        rows.push((removed.get(i).copied(), added.get(i).copied()));
+    }
+    removed.clear();
+    added.clear();
+}
+
+/// Writes a row of the column view with the left and the right line
+///
+/// # Arguments
+///
+/// * `output` - the writer for the row
+/// * `width` - the terminal width for the column widths
+/// * `ln_width` - linenumber column width for indent
+/// * `lines` - the lines of the hunk
+/// * `emphasis` - the changed parts of the lines of the hunk
+/// * `highlight` - the syntax highlighting of the lines of the hunk
+/// * `row` - the indices of the left and right line of the row
+///
+fn write_line_content_split(
+    output: &mut impl Write,
+    width: &usize,
+    ln_width: &usize,
+    lines: &[LINE],
+    emphasis: &[Option<Emphasis>],
+    highlight: &[Option<Highlight>],
+    row: SplitRow,
+) -> io::Result<()> {
+    let half = width / 2;
+    let left_width = half.saturating_sub(ln_width + 1);
+    let right_width = width.saturating_sub(half + ln_width);
+
+    write_line_content_column(
+        output,
+        ln_width,
+        &left_width,
+        row.0
+            .map(|i| (&lines[i], emphasis[i].as_ref(), highlight[i].as_ref())),
+        true,
+    )?;
+    write_chars(
+        output,
+        Colour::Fixed(FIXED_COLOUR).normal(),
+        1,
+        |_| LINENUMBER_SEPERATOR,
+        "",
+    )?;
+    write_line_content_column(
+        output,
+        ln_width,
+        &right_width,
+        row.1
+            .map(|i| (&lines[i], emphasis[i].as_ref(), highlight[i].as_ref())),
+        false,
+    )?;
+    writeln!(output)
+}
+
+/// Writes one side of a row in the column view, fitted to the column width
+///
+/// # Arguments
+///
+/// * `output` - the writer for the row
+/// * `ln_width` - linenumber column width for indent
+/// * `width` - the width of the line content in the column
+/// * `line` - the line object with its changed parts and syntax highlighting
+///   or `None` for an empty filler line
+/// * `left` - if it is the left column, to choose the linenumber of unchanged
+///   lines
+///
+fn write_line_content_column(
+    output: &mut impl Write,
+    ln_width: &usize,
+    width: &usize,
+    line: Option<(&LINE, Option<&Emphasis>, Option<&Highlight>)>,
+    left: bool,
+) -> io::Result<()> {
+    match line {
+        Some((line, emphasis, highlight)) => {
+            match line {
+                LINE::ADD { number, .. }
+                | LINE::REM { number, .. }
+                | LINE::COMBINED { number, .. } => write_line_number(output, ln_width, number)?,
+                LINE::NOP {
+                    number_left,
+                    number_right,
+                    ..
+                } => {
+                    let number = if left { number_left } else { number_right };
+                    write_line_number(output, ln_width, number)?
+                }
+            }
+            write_segments(
+                output,
+                &get_line_segments(line, emphasis, highlight),
+                Some(width),
+            )
+        }
+        None => {
+            write_empty_line_number(output, ln_width)?;
+            write!(output, "{:width$}", "", width = width)
+        }
+    }
 }
 /* --------------------------------------------------------- */
 /* ------------------------- TESTS ------------------------- */
@@ -236,31 +1278,569 @@ mod tests {
 
     use super::super::file::Hunk;
     use super::*;
+    use crate::parser::parse_sections;
+
+    /// Returns the output of a write function as a string
+    fn write_to_string(write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+        let mut output = Vec::new();
+        write(&mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
     #[test]
     fn print_file_test() {
-        let term_width = term_size::dimensions().unwrap().0;
-        let expected_output = format!("{}   \u{1b}[38;5;244m│\u{1b}[0m \u{1b}[1;33mM\u{1b}[0m \u{1b}[1mfilename.rs\u{1b}[0m \u{1b}[1;34m@\u{1b}[0m\u{1b}[34m23jh23lkl\u{1b}[0m\n{} \u{1b}[38;5;244m4\u{1b}[0m \u{1b}[38;5;244m│\u{1b}[0m\u{1b}[32m+added line...\u{1b}[0m\n \u{1b}[38;5;244m6\u{1b}[0m \u{1b}[38;5;244m│\u{1b}[0m\u{1b}[37m line...\u{1b}[0m\n \u{1b}[38;5;244m9\u{1b}[0m \u{1b}[38;5;244m│\u{1b}[0m\u{1b}[31m-removed line...\u{1b}[0m\n{}", get_horizontal_line(&term_width, &4, LINE_ANCHOR_UP), get_horizontal_line(&term_width, &4, LINE_ANCHOR_MIDDLE), get_horizontal_line(&term_width, &4, LINE_ANCHOR_DOWN));
+        let term_width = get_term_width();
+        let expected_output = format!("{}   \u{1b}[38;5;244m│\u{1b}[0m \u{1b}[1;33mM\u{1b}[0m \u{1b}[1mfilename.rs\u{1b}[0m \u{1b}[1;34m@\u{1b}[0m\u{1b}[34m23jh23lkl\u{1b}[0m\n{} \u{1b}[38;5;244m4\u{1b}[0m \u{1b}[38;5;244m│\u{1b}[0m\u{1b}[32m+added line...\u{1b}[0m\n \u{1b}[38;5;244m6\u{1b}[0m \u{1b}[38;5;244m│\u{1b}[0m\u{1b}[37m line...\u{1b}[0m\n \u{1b}[38;5;244m9\u{1b}[0m \u{1b}[38;5;244m│\u{1b}[0m\u{1b}[31m-removed line...\u{1b}[0m\n{}", write_to_string(|output| write_horizontal_line(output, &term_width, &4, LINE_ANCHOR_UP)), write_to_string(|output| write_horizontal_line(output, &term_width, &4, LINE_ANCHOR_MIDDLE)), write_to_string(|output| write_horizontal_line(output, &term_width, &4, LINE_ANCHOR_DOWN)));
         let file: File = File::new(
             MODIFIER::MODIFIED,
             "filename.rs".into(),
             "23jh23lkl".into(),
-            vec![Hunk::new(vec![
-                LINE::ADD {
-                    number: 4,
-                    line: "added line...".into(),
-                },
-                LINE::NOP {
-                    number_left: 5,
-                    number_right: 6,
-                    line: "line...".into(),
+            vec![Hunk::new(
+                (5, 2, 4, 2),
+                "".to_string(),
+                vec![
+                    LINE::ADD {
+                        number: 4,
+                        line: "added line...".into(),
+                        no_newline: false,
+                    },
+                    LINE::NOP {
+                        number_left: 5,
+                        number_right: 6,
+                        line: "line...".into(),
+                        no_newline: false,
+                    },
+                    LINE::REM {
+                        number: 9,
+                        line: "removed line...".into(),
+                        no_newline: false,
+                    },
+                ],
+            )],
+        );
+
+        assert_eq!(
+            expected_output,
+            write_to_string(|output| print(
+                output,
+                &[SECTION::FILE(file)],
+                &PrintOptions {
+                    columnview: false,
+                    syntax: false,
+                    width: None,
+                    colour: true,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn get_split_rows_test() {
+        let nop = LINE::NOP {
+            number_left: 1,
+            number_right: 1,
+            line: "line...".into(),
+            no_newline: false,
+        };
+        let rem = LINE::REM {
+            number: 2,
+            line: "removed line...".into(),
+            no_newline: false,
+        };
+        let add = LINE::ADD {
+            number: 2,
+            line: "added line...".into(),
+            no_newline: false,
+        };
+        let lines = vec![
+            nop.clone(),
+            rem.clone(),
+            rem.clone(),
+            add.clone(),
+            rem.clone(),
+            nop.clone(),
+        ];
+        assert_eq!(
+            vec![
+                (Some(0), Some(0)),
+                (Some(1), Some(3)),
+                (Some(2), None),
+                (Some(4), None),
+                (Some(5), Some(5)),
+            ],
+            get_split_rows(&lines)
+        );
+    }
+
+    #[test]
+    fn write_line_content_split_test() {
+        let lines = vec![LINE::REM {
+            number: 9,
+            line: "removed line...".into(),
+            no_newline: false,
+        }];
+        let expected_output = "  \u{1b}[38;5;244m9\u{1b}[0m \u{1b}[38;5;244m│\u{1b}[0m\u{1b}[31m-removed…\u{1b}[0m\u{1b}[38;5;244m│\u{1b}[0m    \u{1b}[38;5;244m│\u{1b}[0m          \n";
+        assert_eq!(
+            expected_output,
+            write_to_string(|output| write_line_content_split(
+                output,
+                &30,
+                &5,
+                &lines,
+                &[None],
+                &[None],
+                (Some(0), None)
+            ))
+        );
+    }
+
+    #[test]
+    fn write_segments_test() {
+        let style = Colour::Red.normal();
+        let segments = vec![(style, "-".to_string()), (style, "line".to_string())];
+        assert_eq!(
+            "\u{1b}[31m-line\u{1b}[0m",
+            write_to_string(|output| write_segments(output, &segments, None))
+        );
+        assert_eq!(
+            "\u{1b}[31m-line  \u{1b}[0m",
+            write_to_string(|output| write_segments(output, &segments, Some(&7)))
+        );
+        assert_eq!(
+            "\u{1b}[31m-li…\u{1b}[0m",
+            write_to_string(|output| write_segments(output, &segments, Some(&4)))
+        );
+        let segments = vec![(style, "\ta".to_string())];
+        assert_eq!(
+            "\u{1b}[31m    a\u{1b}[0m",
+            write_to_string(|output| write_segments(output, &segments, Some(&5)))
+        );
+    }
+
+    #[test]
+    fn tokenize_test() {
+        assert_eq!(
+            vec!["echo", " ", "\"", "Test", " ", "is", "\""],
+            tokenize("echo \"Test is\"")
+        );
+    }
+
+    #[test]
+    fn get_emphasis_test() {
+        assert_eq!(
+            Some((
+                vec![("echo \"Test\"".to_string(), false)],
+                vec![
+                    ("echo \"Test".to_string(), false),
+                    (" is going on".to_string(), true),
+                    ("\"".to_string(), false),
+                ]
+            )),
+            get_emphasis("echo \"Test\"", "echo \"Test is going on\"")
+        );
+        assert_eq!(None, get_emphasis("completely", "different"));
+    }
+
+    #[test]
+    fn write_line_content_emphasis_test() {
+        let line = LINE::ADD {
+            number: 3,
+            line: "a b".into(),
+            no_newline: false,
+        };
+        let emphasis = vec![("a ".to_string(), false), ("b".to_string(), true)];
+        assert_eq!(
+            " \u{1b}[38;5;244m3\u{1b}[0m \u{1b}[38;5;244m│\u{1b}[0m\u{1b}[32m+a \u{1b}[0m\u{1b}[48;5;22;32mb\u{1b}[0m\n",
+            write_to_string(|output| write_line_content(output, &4, &line, Some(&emphasis), None))
+        );
+    }
+
+    #[test]
+    fn get_line_segments_highlight_test() {
+        let line = LINE::ADD {
+            number: 3,
+            line: "a b".into(),
+            no_newline: false,
+        };
+        let emphasis = vec![("a ".to_string(), false), ("b".to_string(), true)];
+        let highlight = vec![
+            (Colour::Blue, "a".to_string()),
+            (Colour::Yellow, " b".to_string()),
+        ];
+        assert_eq!(
+            vec![
+                (Colour::Green.normal(), "+".to_string()),
+                (
+                    Colour::Blue.on(Colour::Fixed(SYNTAX_ADD_COLOUR)),
+                    "a".to_string()
+                ),
+                (
+                    Colour::Yellow.on(Colour::Fixed(SYNTAX_ADD_COLOUR)),
+                    " ".to_string()
+                ),
+                (
+                    Colour::Yellow.on(Colour::Fixed(SYNTAX_EMPHASIS_ADD_COLOUR)),
+                    "b".to_string()
+                ),
+            ],
+            get_line_segments(&line, Some(&emphasis), Some(&highlight))
+        );
+    }
+
+    #[test]
+    fn get_line_segments_combined_test() {
+        let line = LINE::COMBINED {
+            number: 3,
+            parents: vec![CHANGE::REM, CHANGE::NOP],
+            line: "two(1);".into(),
+            no_newline: false,
+        };
+        assert_eq!(
+            vec![
+                (Colour::Red.normal(), "-".to_string()),
+                (Colour::White.normal(), " ".to_string()),
+                (Colour::Red.normal(), "two(1);".to_string()),
+            ],
+            get_line_segments(&line, None, None)
+        );
+    }
+
+    #[test]
+    fn write_cut_test() {
+        assert_eq!(
+            "\u{1b}[38;5;244m⸝⸜⸝⸜\u{1b}[0m\u{1b}[3;38;5;244m fn main() \u{1b}[0m\u{1b}[38;5;244m⸝⸜⸝⸜⸝\u{1b}[0m\n\u{1b}[38;5;244m⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌\u{1b}[0m\n",
+            write_to_string(|output| write_cut(output, &20, "fn main()"))
+        );
+        assert_eq!(
+            "\u{1b}[38;5;244m⸝⸜⸝⸜⸝⸜\u{1b}[0m\n\u{1b}[38;5;244m⸍⸌⸍⸌⸍⸌\u{1b}[0m\n",
+            write_to_string(|output| write_cut(output, &6, ""))
+        );
+    }
+
+    #[test]
+    fn write_line_content_no_newline_test() {
+        let line = LINE::REM {
+            number: 3,
+            line: "last line".into(),
+            no_newline: true,
+        };
+        assert_eq!(
+            " \u{1b}[38;5;244m3\u{1b}[0m \u{1b}[38;5;244m│\u{1b}[0m\u{1b}[31m-last line\u{1b}[0m\u{1b}[38;5;244m⌀\u{1b}[0m\n",
+            write_to_string(|output| write_line_content(output, &4, &line, None, None))
+        );
+    }
+
+    #[test]
+    fn write_binary_test() {
+        let binary = Binary {
+            old_size: Some(BINARY_SIZE::LITERAL(18)),
+            new_size: Some(BINARY_SIZE::LITERAL(3300)),
+        };
+        assert_eq!(
+            "   \u{1b}[38;5;244m│\u{1b}[0m\u{1b}[3m binary changed (18 B → 3.2 KiB)\u{1b}[0m\n",
+            write_to_string(|output| write_binary(output, &4, &binary))
+        );
+        let binary = Binary {
+            old_size: None,
+            new_size: None,
+        };
+        assert_eq!(
+            "   \u{1b}[38;5;244m│\u{1b}[0m\u{1b}[3m binary changed\u{1b}[0m\n",
+            write_to_string(|output| write_binary(output, &4, &binary))
+        );
+        assert_eq!("Δ12 B", format_binary_size(&BINARY_SIZE::DELTA(12)));
+        assert_eq!(
+            "1.5 MiB",
+            format_binary_size(&BINARY_SIZE::LITERAL(1572864))
+        );
+    }
+
+    #[test]
+    fn write_filename_renamed_test() {
+        let mut file = File::new(
+            MODIFIER::RENAMED,
+            "list_renamed.txt".into(),
+            "33e4d8e".into(),
+            vec![],
+        );
+        file.old_filename = Some("list.txt".into());
+        file.similarity = Some(92);
+        assert_eq!(
+            "   \u{1b}[38;5;244m│\u{1b}[0m \u{1b}[1;35mR\u{1b}[0m \u{1b}[1mlist.txt → list_renamed.txt\u{1b}[0m\u{1b}[38;5;244m (92%)\u{1b}[0m \u{1b}[1;34m@\u{1b}[0m\u{1b}[34m33e4d8e\u{1b}[0m\n",
+            write_to_string(|output| write_filename(output, &file, &4))
+        );
+    }
+
+    #[test]
+    fn print_no_hunks_test() {
+        let term_width = get_term_width();
+        let sections = parse_sections(include_str!("../test/resources/no_hunks.patch"));
+        let body_lines = [
+            "empty file added",
+            "binary changed",
+            "renamed without changes",
+            "only the file mode changed",
+            "empty file deleted",
+        ];
+        let expected_output: String = sections
+            .iter()
+            .zip(body_lines.iter())
+            .map(|(section, body_line)| {
+                let file = match section {
+                    SECTION::FILE(file) => file,
+                    SECTION::TEXT(text) => panic!("Unexpected text: {:?}", text),
+                    SECTION::COMMIT(commit) => panic!("Unexpected commit: {:?}", commit),
+                };
+                format!(
+                    "{}{}{}{}{}",
+                    write_to_string(|output| write_horizontal_line(
+                        output,
+                        &term_width,
+                        &4,
+                        LINE_ANCHOR_UP
+                    )),
+                    write_to_string(|output| write_filename(output, file, &4)),
+                    write_to_string(|output| write_horizontal_line(
+                        output,
+                        &term_width,
+                        &4,
+                        LINE_ANCHOR_MIDDLE
+                    )),
+                    write_to_string(|output| write_body_line(output, &4, body_line)),
+                    write_to_string(|output| write_horizontal_line(
+                        output,
+                        &term_width,
+                        &4,
+                        LINE_ANCHOR_DOWN
+                    )),
+                )
+            })
+            .collect();
+        assert_eq!(5, sections.len());
+        assert_eq!(
+            expected_output,
+            write_to_string(|output| print(
+                output,
+                &sections,
+                &PrintOptions {
+                    columnview: false,
+                    syntax: true,
+                    width: None,
+                    colour: true,
+                }
+            ))
+        );
+        assert_eq!(
+            expected_output,
+            write_to_string(|output| print(
+                output,
+                &sections,
+                &PrintOptions {
+                    columnview: true,
+                    syntax: true,
+                    width: None,
+                    colour: true,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn print_empty_hunk_test() {
+        let file = File::new(
+            MODIFIER::MODIFIED,
+            "list.txt".into(),
+            "".into(),
+            vec![Hunk::new((0, 0, 0, 0), "".into(), vec![])],
+        );
+        assert_eq!(0, file.get_max_line_number_size());
+        assert!(!write_to_string(|output| print(
+            output,
+            &[SECTION::FILE(file)],
+            &PrintOptions {
+                columnview: true,
+                syntax: true,
+                width: None,
+                colour: true,
+            }
+        ))
+        .is_empty());
+    }
+
+    #[test]
+    fn write_filename_mode_change_test() {
+        let mut file = File::new(MODIFIER::MODIFIED, "script.sh".into(), "".into(), vec![]);
+        file.old_mode = Some("100644".into());
+        file.new_mode = Some("100755".into());
+        assert_eq!(
+            "   \u{1b}[38;5;244m│\u{1b}[0m \u{1b}[1;33mM\u{1b}[0m \u{1b}[1mscript.sh\u{1b}[0m\u{1b}[38;5;244m 100644 → 100755\u{1b}[0m\n",
+            write_to_string(|output| write_filename(output, &file, &4))
+        );
+    }
+
+    #[test]
+    fn write_commit_test() {
+        let commit = Commit {
+            id: "4644f8d".into(),
+            refs: "(HEAD -> main)".into(),
+            author: "Jane Doe <jane@example.com>".into(),
+            date: "Sat Mar 2 10:00:00 2019 +0100".into(),
+            message: "Fix the parser\n\nIt panicked.".into(),
+            series: None,
+            files: Vec::new(),
+        };
+        assert_eq!(
+            "\u{1b}[33m━━━━━━━━━\n\u{1b}[0m \u{1b}[1;33m4644f8d\u{1b}[0m \u{1b}[1;36m(HEAD -> main)\u{1b}[0m\n \u{1b}[38;5;244mAuthor:\u{1b}[0m Jane Doe <jane@example.com>\n \u{1b}[38;5;244mDate:\u{1b}[0m Sat Mar 2 10:00:00 2019 +0100\n\n \u{1b}[1mFix the parser\u{1b}[0m\n\n It panicked.\n\u{1b}[33m━━━━━━━━━\n\u{1b}[0m",
+            write_to_string(|output| write_commit(output, &commit, &10))
+        );
+    }
+
+    #[test]
+    fn print_text_test() {
+        let sections = vec![
+            SECTION::TEXT("commit 2f1d3a4\n".into()),
+            SECTION::FILE(File::new(
+                MODIFIER::ADD,
+                "empty.txt".into(),
+                "e69de29".into(),
+                vec![],
+            )),
+            SECTION::TEXT("not a diff\n".into()),
+        ];
+        let output = write_to_string(|output| {
+            print(
+                output,
+                &sections,
+                &PrintOptions {
+                    columnview: false,
+                    syntax: false,
+                    width: None,
+                    colour: true,
                 },
-                LINE::REM {
-                    number: 9,
-                    line: "removed line...".into(),
+            )
+        });
+        assert!(output.starts_with("commit 2f1d3a4\n"));
+        assert!(output.ends_with("not a diff\n"));
+        assert!(output.contains("empty file added"));
+    }
+
+    #[test]
+    fn print_plain_test() {
+        let file = File::new(MODIFIER::ADD, "empty.txt".into(), "e69de29".into(), vec![]);
+        let output = write_to_string(|output| {
+            print(
+                output,
+                &[SECTION::FILE(file)],
+                &PrintOptions {
+                    columnview: false,
+                    syntax: false,
+                    width: Some(20),
+                    colour: false,
                 },
-            ])],
+            )
+        });
+        assert_eq!(
+            "───┬────────────────\n   │ A empty.txt @e69de29\n───┼────────────────\n   │ empty file added\n───┴────────────────\n",
+            output
+        );
+    }
+
+    #[test]
+    fn print_file_headings_test() {
+        let hunk = |start: usize, heading: &str| {
+            Hunk::new(
+                (start, 1, start, 1),
+                heading.to_string(),
+                vec![LINE::NOP {
+                    number_left: start,
+                    number_right: start,
+                    line: "line".into(),
+                    no_newline: false,
+                }],
+            )
+        };
+        let options = PrintOptions {
+            columnview: false,
+            syntax: false,
+            width: Some(20),
+            colour: false,
+        };
+        let file = File::new(
+            MODIFIER::MODIFIED,
+            "main.rs".into(),
+            String::new(),
+            vec![hunk(1, "fn first()"), hunk(10, "fn second()")],
+        );
+        let output = write_to_string(|output| print(output, &[SECTION::FILE(file)], &options));
+        assert_eq!(
+            "────┬───────────────\n    │ M main.rs\n────┼───────────────\n    │ fn first()\n  1 │ line\n⸝⸜⸝⸜ fn second() ⸝⸜⸝\n⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌⸍⸌\n 10 │ line\n────┴───────────────\n",
+            output
+        );
+
+        // a single hunk has its heading as well
+        let file = File::new(
+            MODIFIER::MODIFIED,
+            "main.rs".into(),
+            String::new(),
+            vec![hunk(1, "fn first()")],
         );
+        let output = write_to_string(|output| print(output, &[SECTION::FILE(file)], &options));
+        assert!(output.contains("│ fn first()\n 1 │ line\n"));
+    }
+
+    #[test]
+    fn print_combined_three_parents_test() {
+        let line = |number: usize, parents: Vec<CHANGE>, line: &str| LINE::COMBINED {
+            number,
+            parents,
+            line: line.into(),
+            no_newline: false,
+        };
+        let file = File::new(
+            MODIFIER::MODIFIED,
+            "main.rs".into(),
+            String::new(),
+            vec![Hunk::new(
+                (1, 2, 1, 2),
+                String::new(),
+                vec![
+                    line(1, vec![CHANGE::NOP, CHANGE::NOP, CHANGE::NOP], "a"),
+                    line(2, vec![CHANGE::REM, CHANGE::NOP, CHANGE::NOP], "b"),
+                    line(2, vec![CHANGE::NOP, CHANGE::REM, CHANGE::NOP], "c"),
+                    line(2, vec![CHANGE::ADD, CHANGE::ADD, CHANGE::ADD], "d"),
+                ],
+            )],
+        );
+        let options = PrintOptions {
+            columnview: true,
+            syntax: false,
+            width: Some(20),
+            colour: false,
+        };
+        // a combined diff is never shown in columns
+        let output = write_to_string(|output| print(output, &[SECTION::FILE(file)], &options));
+        assert_eq!(
+            "───┬────────────────\n   │ M main.rs\n───┼────────────────\n 1 │   a\n 2 │-  b\n 2 │ - c\n 2 │+++d\n───┴────────────────\n",
+            output
+        );
+    }
+
+    #[test]
+    fn write_heading_test() {
+        assert_eq!(
+            "   \u{1b}[38;5;244m│\u{1b}[0m \u{1b}[3;38;5;244mfn main()\u{1b}[0m\n",
+            write_to_string(|output| write_heading(output, &4, "fn main()"))
+        );
+        assert_eq!("", write_to_string(|output| write_heading(output, &4, "")));
+    }
 
-        assert_eq!(expected_output, print(&vec![file], None));
+    #[test]
+    fn plain_writer_test() {
+        let output = write_to_string(|output| {
+            let mut writer = PlainWriter::new(output);
+            writer.write_all(b"\x1b[32m+\tadded\x1b")?;
+            writer.write_all(b"[0m\n")
+        });
+        assert_eq!("+\tadded\n", output);
     }
 }
@@ -0,0 +1,165 @@
+//! Language-aware highlighting of the line contents. The syntax is chosen by
+//! the extension of the filename and the highlighting state is kept over the
+//! lines of a hunk, so multi-line strings and comments are highlighted right.
+
+use ansi_term::Colour;
+use std::path::Path;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+
+use crate::file::{CHANGE, LINE};
+
+// theme out of the syntect default themes
+const THEME: &str = "base16-ocean.dark";
+// syntax without any highlighting
+const PLAIN_TEXT: &str = "Plain Text";
+
+/// The parts of a line content with their foreground colour
+pub type Highlight = Vec<(Colour, String)>;
+
+pub struct Highlighter {
+    syntax_set: SyntaxSet,
+    theme: Theme,
+}
+
+impl Highlighter {
+    pub fn new() -> Highlighter {
+        let mut theme_set = ThemeSet::load_defaults();
+        Highlighter {
+            syntax_set: SyntaxSet::load_defaults_newlines(),
+            theme: theme_set.themes.remove(THEME).unwrap_or_default(),
+        }
+    }
+
+    /// Returns the highlighted parts of every line in a hunk. The old and the
+    /// new version of the file have their own state, unchanged lines
+    /// continue both of them. All lines are `None` if there is no syntax for
+    /// the filename.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - the filename to choose the syntax
+    /// * `lines` - the lines of a hunk
+    ///
+    pub fn highlight_hunk(&self, filename: &str, lines: &[LINE]) -> Vec<Option<Highlight>> {
+        let syntax = match self.get_syntax(filename) {
+            Some(syntax) => syntax,
+            None => return vec![None; lines.len()],
+        };
+        let mut left = HighlightLines::new(syntax, &self.theme);
+        let mut right = HighlightLines::new(syntax, &self.theme);
+
+        lines
+            .iter()
+            .map(|line| match line {
+                LINE::ADD { line, .. } => self.highlight_line(&mut right, line),
+                LINE::REM { line, .. } => self.highlight_line(&mut left, line),
+                LINE::NOP { line, .. } => {
+                    self.highlight_line(&mut left, line);
+                    self.highlight_line(&mut right, line)
+                }
+                // removed lines of a combined diff continue the state of the
+                // parents, all others the state of the result
+                LINE::COMBINED { line: content, .. } => match line.get_change() {
+                    CHANGE::REM => self.highlight_line(&mut left, content),
+                    _ => self.highlight_line(&mut right, content),
+                },
+            })
+            .collect()
+    }
+
+    /// Returns the syntax by the extension or the name of the file
+    fn get_syntax(&self, filename: &str) -> Option<&SyntaxReference> {
+        let path = Path::new(filename);
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| self.syntax_set.find_syntax_by_extension(extension))
+            .or_else(|| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| self.syntax_set.find_syntax_by_extension(name))
+            })
+            .filter(|syntax| syntax.name != PLAIN_TEXT)
+    }
+
+    /// Returns the parts of a line with their colour, neighbouring parts with
+    /// the same colour are joined
+    fn highlight_line(
+        &self,
+        highlighter: &mut HighlightLines<'_>,
+        line: &str,
+    ) -> Option<Highlight> {
+        let line = format!("{}\n", line);
+        let ranges = highlighter.highlight_line(&line, &self.syntax_set).ok()?;
+
+        let mut highlight: Highlight = Vec::new();
+        for (style, text) in ranges {
+            let colour = Colour::RGB(style.foreground.r, style.foreground.g, style.foreground.b);
+            let text = text.trim_end_matches('\n');
+            match highlight.last_mut() {
+                Some((last_colour, last_text)) if *last_colour == colour => {
+                    last_text.push_str(text)
+                }
+                _ if text.is_empty() => {}
+                _ => highlight.push((colour, text.to_string())),
+            }
+        }
+
+        Some(highlight)
+    }
+}
+
+/* --------------------------------------------------------- */
+/* ------------------------- TESTS ------------------------- */
+/* --------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_hunk_without_syntax_test() {
+        let lines = vec![LINE::ADD {
+            number: 1,
+            line: "apples".into(),
+            no_newline: false,
+        }];
+        assert_eq!(
+            vec![None],
+            Highlighter::new().highlight_hunk("list.txt", &lines)
+        );
+    }
+
+    #[test]
+    fn highlight_hunk_multi_line_comment_test() {
+        let lines = vec![
+            LINE::NOP {
+                number_left: 1,
+                number_right: 1,
+                line: "/* comment".into(),
+                no_newline: false,
+            },
+            LINE::REM {
+                number: 2,
+                line: "removed".into(),
+                no_newline: false,
+            },
+            LINE::ADD {
+                number: 2,
+                line: "added */ fn main() {}".into(),
+                no_newline: false,
+            },
+        ];
+        let highlight = Highlighter::new().highlight_hunk("main.rs", &lines);
+        let comment = highlight[0].as_ref().unwrap()[0].0;
+
+        // the comment continues on both sides
+        assert_eq!(
+            &vec![(comment, "removed".to_string())],
+            highlight[1].as_ref().unwrap()
+        );
+        let added = highlight[2].as_ref().unwrap();
+        assert_eq!((comment, "added */".to_string()), added[0]);
+        assert!(added.iter().any(|(colour, _)| *colour != comment));
+    }
+}